@@ -32,6 +32,7 @@ fn main() {
 
     let mut keyword_defs = vec![];
     let mut match_arms = vec![];
+    let languages = langs.keys().cloned().collect::<Vec<_>>();
 
     for (lang, data) in langs {
         let lang_upper = lang.to_shouty_snake_case();
@@ -90,6 +91,12 @@ fn main() {
     }
     .to_string();
 
+    let languages = quote! {
+        [#(#languages),*]
+    }
+    .to_string();
+
     std::fs::write(out_dir.join("keywords.gen.rs"), keyword_defs).unwrap();
+    std::fs::write(out_dir.join("languages.gen.rs"), languages).unwrap();
     std::fs::write(out_dir.join("match.gen.rs"), match_arms).unwrap();
 }
@@ -0,0 +1,774 @@
+// Copyright (c) 2026  Brendan Molloy <brendan@bbqsrc.net>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Lossless concrete syntax tree of a `.feature` file.
+//!
+//! A [`SyntaxTree`] keeps the source it was parsed from along with its
+//! [`GherkinDocument`], splitting the whole source into [`SyntaxToken`]s,
+//! whitespace and comments included, grouped into [`SyntaxNode`]s for the
+//! nodes of the document. Writing out the tokens reproduces the source byte
+//! for byte, so tools like formatters can rewrite some nodes while leaving the
+//! rest of the file untouched.
+//!
+//! ```
+//! # use gherkin::{cst::{SyntaxTree, TokenKind}, GherkinEnv};
+//! #
+//! let input = "Feature: Foo\n  # A comment\n  Scenario:   Bar \n    Given a step\n";
+//! let tree = SyntaxTree::parse(input, GherkinEnv::default()).unwrap();
+//! assert_eq!(tree.to_string(), input);
+//!
+//! let scenario = &tree.document().feature.as_ref().unwrap().scenarios[0];
+//! assert_eq!(tree.text_of(scenario.span), "Scenario:   Bar \n    Given a step");
+//!
+//! let keywords = tree
+//!     .tokens()
+//!     .filter(|t| t.kind == TokenKind::Keyword)
+//!     .map(|t| tree.text_of(t.span))
+//!     .collect::<Vec<_>>();
+//! assert_eq!(keywords, ["Feature", "Scenario", "Given "]);
+//! ```
+
+use std::fmt;
+
+use crate::{
+    Background, Examples, Feature, GherkinDocument, GherkinEnv, ParseError, Rule, Scenario, Span,
+    Step, Table,
+};
+
+/// Lossless concrete syntax tree of a `.feature` file, along with its
+/// [`GherkinDocument`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxTree {
+    source: String,
+    document: GherkinDocument,
+    root: SyntaxNode,
+}
+
+impl SyntaxTree {
+    /// Parses the `input` into its [`GherkinDocument`] and the tree of its
+    /// tokens.
+    ///
+    /// Keywords are only told from the text following them when spelled as in
+    /// the language definition, so in [`ParseMode::Forgiving`] the misspelled
+    /// ones are [`TokenKind::Text`].
+    ///
+    /// [`ParseMode::Forgiving`]: crate::ParseMode::Forgiving
+    pub fn parse<S: AsRef<str>>(input: S, env: GherkinEnv) -> Result<SyntaxTree, ParseError> {
+        let source = input.as_ref();
+        let document = GherkinDocument::parse(source, env)?;
+        let root = Builder::new(source, &document).build();
+
+        Ok(SyntaxTree {
+            source: source.into(),
+            document,
+            root,
+        })
+    }
+
+    /// Returns the source the tree was parsed from.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the [`GherkinDocument`] parsed from the source.
+    pub fn document(&self) -> &GherkinDocument {
+        &self.document
+    }
+
+    /// Returns the [`NodeKind::Document`] node spanning the whole source.
+    pub fn root(&self) -> &SyntaxNode {
+        &self.root
+    }
+
+    /// Returns all the tokens of the tree, in order.
+    pub fn tokens(&self) -> Tokens<'_> {
+        self.root.tokens()
+    }
+
+    /// Returns the text of the source at the `span`, like the one of a
+    /// [`SyntaxToken`] or of a node of the [`GherkinDocument`], clamped to the
+    /// end of the source.
+    pub fn text_of(&self, span: Span) -> &str {
+        let end = span.end.min(self.source.len());
+        &self.source[span.start.min(end)..end]
+    }
+}
+
+impl fmt::Display for SyntaxTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for token in self.tokens() {
+            f.write_str(self.text_of(token.span))?;
+        }
+        Ok(())
+    }
+}
+
+/// Kind of a [`SyntaxNode`], after the node of the [`GherkinDocument`] it
+/// stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    Document,
+    Feature,
+    Background,
+    Rule,
+    Scenario,
+    Examples,
+    Step,
+    DocString,
+    Table,
+    TableRow,
+}
+
+/// Node of a [`SyntaxTree`], spanning its tags, its own lines and the ones of
+/// its children.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxNode {
+    /// The kind of the node.
+    pub kind: NodeKind,
+    /// The `(start, end)` offset the node spans in the source.
+    pub span: Span,
+    /// The tokens and nodes the node consists of, in order.
+    pub children: Vec<SyntaxElement>,
+}
+
+impl SyntaxNode {
+    /// Returns all the tokens of the node, including the ones of its
+    /// children, in order.
+    pub fn tokens(&self) -> Tokens<'_> {
+        Tokens {
+            stack: vec![self.children.iter()],
+        }
+    }
+}
+
+/// Child of a [`SyntaxNode`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+/// Token of a [`SyntaxTree`], its text being
+/// [`SyntaxTree::text_of()`] its `span`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SyntaxToken {
+    /// The kind of the token.
+    pub kind: TokenKind,
+    /// The `(start, end)` offset the token spans in the source.
+    pub span: Span,
+}
+
+/// Kind of a [`SyntaxToken`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// Spaces and tabs.
+    Whitespace,
+    /// A `\n` or `\r\n` line break.
+    Newline,
+    /// A `#` comment, without its indentation.
+    Comment,
+    /// A `# language:` directive.
+    LanguageDirective,
+    /// A tag, including its `@`.
+    Tag,
+    /// A keyword as spelled in the source, like `Scenario` or `Given `.
+    Keyword,
+    /// A `:` after a keyword.
+    Colon,
+    /// The name following a keyword.
+    Name,
+    /// The text of a step.
+    StepText,
+    /// A line of a description, without its indentation.
+    Description,
+    /// The opening or closing delimiter of a docstring.
+    DocStringDelimiter,
+    /// The media type following the opening delimiter of a docstring.
+    MediaType,
+    /// A line of the content of a docstring, including its indentation.
+    DocStringLine,
+    /// A `|` of a table row.
+    Pipe,
+    /// The value of a table cell, as written.
+    Cell,
+    /// Any other text.
+    Text,
+}
+
+/// Iterator over the tokens of a [`SyntaxNode`], returned by
+/// [`SyntaxNode::tokens()`].
+#[derive(Debug, Clone)]
+pub struct Tokens<'a> {
+    stack: Vec<std::slice::Iter<'a, SyntaxElement>>,
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = &'a SyntaxToken;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.stack.last_mut()?.next() {
+                Some(SyntaxElement::Token(token)) => return Some(token),
+                Some(SyntaxElement::Node(node)) => self.stack.push(node.children.iter()),
+                None => _ = self.stack.pop(),
+            }
+        }
+    }
+}
+
+/// Node of the [`GherkinDocument`] to build a [`SyntaxNode`] of.
+struct Pending {
+    kind: NodeKind,
+    span: Span,
+    children: Vec<Pending>,
+}
+
+impl Pending {
+    /// Creates a node starting at the `start` of its own or of its tags,
+    /// spanning its `children` too.
+    fn new(kind: NodeKind, start: usize, span: Span, children: Vec<Pending>) -> Self {
+        let end = children
+            .iter()
+            .map(|c| c.span.end)
+            .fold(span.end, usize::max);
+        Self {
+            kind,
+            span: Span {
+                start: start.min(span.start),
+                end,
+            },
+            children,
+        }
+    }
+}
+
+struct Builder<'a> {
+    source: &'a str,
+    /// The tokens found in the nodes of the document, sorted.
+    tokens: Vec<SyntaxToken>,
+    next: usize,
+    root: Pending,
+}
+
+impl<'a> Builder<'a> {
+    fn new(source: &'a str, document: &GherkinDocument) -> Self {
+        let mut builder = Builder {
+            source,
+            tokens: vec![],
+            next: 0,
+            root: Pending {
+                kind: NodeKind::Document,
+                span: Span {
+                    start: 0,
+                    end: source.len(),
+                },
+                children: vec![],
+            },
+        };
+
+        if let Some(directive) = &document.language_directive {
+            builder.token(TokenKind::LanguageDirective, directive.span);
+        }
+        for comment in &document.comments {
+            let indent = comment.text.len() - comment.text.trim_start().len();
+            let span = Span {
+                start: comment.span.start + indent,
+                end: comment.span.end,
+            };
+            builder.token(TokenKind::Comment, span);
+        }
+        if let Some(feature) = &document.feature {
+            let feature = builder.feature(feature);
+            builder.root.children.push(feature);
+        }
+
+        builder.tokens.sort_by_key(|t| t.span.start);
+        builder
+    }
+
+    fn build(mut self) -> SyntaxNode {
+        let root = std::mem::replace(
+            &mut self.root,
+            Pending {
+                kind: NodeKind::Document,
+                span: Span::default(),
+                children: vec![],
+            },
+        );
+        self.node(root)
+    }
+
+    /// Records a token of the `kind` at the `span`, if it's within the source
+    /// and not empty.
+    fn token(&mut self, kind: TokenKind, span: Span) {
+        let end = span.end.min(self.source.len());
+        if span.start < end {
+            self.tokens.push(SyntaxToken {
+                kind,
+                span: Span {
+                    start: span.start,
+                    end,
+                },
+            });
+        }
+    }
+
+    /// Records the tokens of the `keyword` starting at `start`, followed by
+    /// an optional colon and the `name`, returning where they end.
+    fn title(&mut self, start: usize, keyword: &str, name: &str, name_kind: TokenKind) -> usize {
+        let Some(rest) = self.source.get(start..) else {
+            return start;
+        };
+        if !rest.starts_with(keyword) {
+            return start;
+        }
+        let mut end = start + keyword.len();
+        self.token(TokenKind::Keyword, Span { start, end });
+
+        let line = self.source[end..].split('\n').next().unwrap_or_default();
+        let after_colon = line.trim_start_matches([' ', '\t']);
+        let after_colon = after_colon.strip_prefix(':').unwrap_or(after_colon);
+        let text = after_colon.trim_start_matches([' ', '\t']);
+        if !name.is_empty() && text.starts_with(name) {
+            end += line.len() - text.len();
+            self.token(
+                name_kind,
+                Span {
+                    start: end,
+                    end: end + name.len(),
+                },
+            );
+            end += name.len();
+        }
+        end
+    }
+
+    fn feature(&mut self, feature: &Feature) -> Pending {
+        let start = self.tags(&feature.tags, feature.span);
+        self.title(
+            feature.span.start,
+            &feature.keyword,
+            &feature.name,
+            TokenKind::Name,
+        );
+        self.description(feature.description.as_ref().map(|d| d.span));
+
+        let mut children = vec![];
+        if let Some(background) = &feature.background {
+            children.push(self.background(background));
+        }
+        for scenario in &feature.scenarios {
+            children.push(self.scenario(scenario));
+        }
+        for rule in &feature.rules {
+            children.push(self.rule(rule));
+        }
+        Pending::new(NodeKind::Feature, start, feature.span, children)
+    }
+
+    fn rule(&mut self, rule: &Rule) -> Pending {
+        let start = self.tags(&rule.tags, rule.span);
+        self.title(rule.span.start, &rule.keyword, &rule.name, TokenKind::Name);
+        self.description(rule.description.as_ref().map(|d| d.span));
+
+        let mut children = vec![];
+        if let Some(background) = &rule.background {
+            children.push(self.background(background));
+        }
+        for scenario in &rule.scenarios {
+            children.push(self.scenario(scenario));
+        }
+        Pending::new(NodeKind::Rule, start, rule.span, children)
+    }
+
+    fn background(&mut self, background: &Background) -> Pending {
+        self.title(
+            background.span.start,
+            &background.keyword,
+            &background.name,
+            TokenKind::Name,
+        );
+        self.description(background.description.as_ref().map(|d| d.span));
+
+        let children = background.steps.iter().map(|s| self.step(s)).collect();
+        Pending::new(
+            NodeKind::Background,
+            background.span.start,
+            background.span,
+            children,
+        )
+    }
+
+    fn scenario(&mut self, scenario: &Scenario) -> Pending {
+        let start = self.tags(&scenario.tags, scenario.span);
+        self.title(
+            scenario.span.start,
+            &scenario.keyword,
+            &scenario.name,
+            TokenKind::Name,
+        );
+        self.description(scenario.description.as_ref().map(|d| d.span));
+
+        let mut children = scenario
+            .steps
+            .iter()
+            .map(|s| self.step(s))
+            .collect::<Vec<_>>();
+        for examples in &scenario.examples {
+            children.push(self.examples(examples));
+        }
+        Pending::new(NodeKind::Scenario, start, scenario.span, children)
+    }
+
+    fn examples(&mut self, examples: &Examples) -> Pending {
+        let start = self.tags(&examples.tags, examples.span);
+        self.title(
+            examples.span.start,
+            &examples.keyword,
+            examples.name.as_deref().unwrap_or_default(),
+            TokenKind::Name,
+        );
+        self.description(examples.description.as_ref().map(|d| d.span));
+
+        let children = examples.table.iter().map(|t| self.table(t)).collect();
+        Pending::new(NodeKind::Examples, start, examples.span, children)
+    }
+
+    fn step(&mut self, step: &Step) -> Pending {
+        // Unlike the other keywords, the ones of steps include the space
+        // following them.
+        self.title(
+            step.span.start,
+            &step.keyword,
+            &step.value,
+            TokenKind::StepText,
+        );
+
+        let mut children = vec![];
+        if let Some(docstring) = &step.docstring {
+            let span = docstring.span;
+            let delimiter = docstring.delimiter.len();
+            self.token(
+                TokenKind::DocStringDelimiter,
+                Span {
+                    start: span.start,
+                    end: span.start + delimiter,
+                },
+            );
+            let mut lines = self.lines(Span {
+                start: span.start + delimiter,
+                end: span.end,
+            });
+            if let Some(media_type) = lines.next() {
+                let text = media_type.1.trim_matches([' ', '\t']);
+                if !text.is_empty() {
+                    let start = media_type.0 + media_type.1.find(text).unwrap_or_default();
+                    self.token(
+                        TokenKind::MediaType,
+                        Span {
+                            start,
+                            end: start + text.len(),
+                        },
+                    );
+                }
+            }
+            let mut lines = lines.collect::<Vec<_>>();
+            if let Some((start, line)) = lines.pop() {
+                // The last line ends with the closing delimiter.
+                let indent = line.len() - line.trim_start_matches([' ', '\t']).len();
+                self.token(
+                    TokenKind::DocStringDelimiter,
+                    Span {
+                        start: start + indent,
+                        end: span.end,
+                    },
+                );
+            }
+            for (start, line) in lines {
+                self.token(
+                    TokenKind::DocStringLine,
+                    Span {
+                        start,
+                        end: start + line.len(),
+                    },
+                );
+            }
+            children.push(Pending::new(NodeKind::DocString, span.start, span, vec![]));
+        }
+        if let Some(table) = &step.table {
+            children.push(self.table(table));
+        }
+        Pending::new(NodeKind::Step, step.span.start, step.span, children)
+    }
+
+    fn table(&mut self, table: &Table) -> Pending {
+        let mut rows = vec![];
+        for row in &table.rows {
+            for cell in &row.cells {
+                self.token(TokenKind::Cell, cell.span);
+            }
+            rows.push(Pending::new(
+                NodeKind::TableRow,
+                row.span.start,
+                row.span,
+                vec![],
+            ));
+        }
+        Pending::new(NodeKind::Table, table.span.start, table.span, rows)
+    }
+
+    /// Records the tags, returning where the node having them and starting at
+    /// the `span` starts.
+    fn tags(&mut self, tags: &[crate::Tag], span: Span) -> usize {
+        for tag in tags {
+            self.token(TokenKind::Tag, tag.span);
+        }
+        tags.iter()
+            .map(|t| t.span.start)
+            .fold(span.start, usize::min)
+    }
+
+    fn description(&mut self, span: Option<Span>) {
+        let Some(span) = span else {
+            return;
+        };
+        let lines = self.lines(span).collect::<Vec<_>>();
+        for (start, line) in lines {
+            let text = line.trim_start_matches([' ', '\t']);
+            // The comment lines between the lines are recorded on their own.
+            if !text.is_empty() && !text.starts_with('#') {
+                let start = start + line.len() - text.len();
+                self.token(
+                    TokenKind::Description,
+                    Span {
+                        start,
+                        end: start + text.len(),
+                    },
+                );
+            }
+        }
+    }
+
+    /// Returns the lines of the source at the `span`, along with their
+    /// offsets, without their line breaks.
+    fn lines(&self, span: Span) -> impl Iterator<Item = (usize, &'a str)> {
+        let end = span.end.min(self.source.len());
+        let start = span.start.min(end);
+        let source = self.source;
+        source[start..end]
+            .split('\n')
+            .scan(start, move |offset, line| {
+                let start = *offset;
+                *offset += line.len() + 1;
+                Some((start, line.strip_suffix('\r').unwrap_or(line)))
+            })
+    }
+
+    /// Builds the [`SyntaxNode`] of the `pending` one, filling the gaps
+    /// between its tokens and children with trivia.
+    fn node(&mut self, pending: Pending) -> SyntaxNode {
+        let span = pending.span;
+        let mut children = vec![];
+        let mut offset = span.start;
+        let mut nodes = pending.children.into_iter().peekable();
+
+        loop {
+            // Skips the tokens overlapping the already built ones.
+            while self
+                .tokens
+                .get(self.next)
+                .is_some_and(|t| t.span.start < offset)
+            {
+                self.next += 1;
+            }
+            let token = self
+                .tokens
+                .get(self.next)
+                .copied()
+                .filter(|t| t.span.end <= span.end);
+            let node_start = nodes.peek().map(|n| n.span.start.max(offset));
+
+            let token = token.filter(|t| node_start.is_none_or(|s| t.span.start < s));
+            if let Some(token) = token {
+                self.trivia(offset, token.span.start, &mut children);
+                children.push(SyntaxElement::Token(token));
+                offset = token.span.end;
+                self.next += 1;
+            } else if let Some(start) = node_start {
+                let mut node = nodes.next().unwrap();
+                node.span.start = start;
+                node.span.end = node.span.end.clamp(start, span.end);
+                self.trivia(offset, start, &mut children);
+                offset = node.span.end;
+                children.push(SyntaxElement::Node(self.node(node)));
+            } else {
+                break;
+            }
+        }
+        self.trivia(offset, span.end, &mut children);
+
+        SyntaxNode {
+            kind: pending.kind,
+            span,
+            children,
+        }
+    }
+
+    /// Splits the source between the `start` and `end` offsets into
+    /// [`TokenKind::Whitespace`], [`TokenKind::Newline`],
+    /// [`TokenKind::Colon`], [`TokenKind::Pipe`] and [`TokenKind::Text`]
+    /// tokens.
+    fn trivia(&self, start: usize, end: usize, children: &mut Vec<SyntaxElement>) {
+        let text = &self.source[start..end];
+        let mut offset = start;
+        let mut chars = text.char_indices().peekable();
+
+        while let Some((i, c)) = chars.next() {
+            let kind = match c {
+                ' ' | '\t' => TokenKind::Whitespace,
+                '\n' => TokenKind::Newline,
+                '\r' if chars.peek().is_some_and(|(_, c)| *c == '\n') => {
+                    _ = chars.next();
+                    TokenKind::Newline
+                }
+                ':' => TokenKind::Colon,
+                '|' => TokenKind::Pipe,
+                _ => TokenKind::Text,
+            };
+            let continues = |c: char| match kind {
+                TokenKind::Whitespace => c == ' ' || c == '\t',
+                TokenKind::Text => !matches!(c, ' ' | '\t' | '\n' | '\r' | ':' | '|'),
+                _ => false,
+            };
+            while chars.peek().is_some_and(|(_, c)| continues(*c)) {
+                _ = chars.next();
+            }
+            let next = chars.peek().map_or(text.len(), |(i, _)| *i);
+            children.push(SyntaxElement::Token(SyntaxToken {
+                kind,
+                span: Span {
+                    start: start + i,
+                    end: start + next,
+                },
+            }));
+            offset = start + next;
+        }
+        debug_assert_eq!(offset, end);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+    use crate::ParseMode;
+
+    fn kinds(tree: &SyntaxTree) -> Vec<(TokenKind, &str)> {
+        tree.tokens()
+            .filter(|t| !matches!(t.kind, TokenKind::Whitespace | TokenKind::Newline))
+            .map(|t| (t.kind, tree.text_of(t.span)))
+            .collect()
+    }
+
+    #[test]
+    fn fixtures_are_reproduced() {
+        let d = env!("CARGO_MANIFEST_DIR");
+        let files = fs::read_dir(format!("{}/tests/fixtures/data/good/", d)).unwrap();
+        for file in files {
+            let path = file.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "feature") {
+                continue;
+            }
+            let input = fs::read_to_string(&path).unwrap();
+            let env = GherkinEnv::default().with_mode(ParseMode::Strict);
+            let tree = SyntaxTree::parse(&input, env).unwrap();
+
+            assert_eq!(tree.to_string(), input, "{}", path.display());
+            let mut offset = 0;
+            for token in tree.tokens() {
+                assert_eq!(token.span.start, offset, "{}", path.display());
+                offset = token.span.end;
+            }
+            assert_eq!(offset, input.len(), "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn tokens() {
+        let input = "# language: en\n@tag\nFeature: Foo\n  Some description\n\n  \
+                     Scenario: Bar # not a comment\n    # A comment\n    Given a step\n      \
+                     \"\"\"json\n      {}\n      \"\"\"\n    When a table\n      | a |  |\n";
+        let tree = SyntaxTree::parse(input, GherkinEnv::default()).unwrap();
+
+        use TokenKind::*;
+        assert_eq!(
+            kinds(&tree),
+            [
+                (LanguageDirective, "# language: en"),
+                (Tag, "@tag"),
+                (Keyword, "Feature"),
+                (Colon, ":"),
+                (Name, "Foo"),
+                (Description, "Some description"),
+                (Keyword, "Scenario"),
+                (Colon, ":"),
+                (Name, "Bar # not a comment"),
+                (Comment, "# A comment"),
+                (Keyword, "Given "),
+                (StepText, "a step"),
+                (DocStringDelimiter, "\"\"\""),
+                (MediaType, "json"),
+                (DocStringLine, "      {}"),
+                (DocStringDelimiter, "\"\"\""),
+                (Keyword, "When "),
+                (StepText, "a table"),
+                (Pipe, "|"),
+                (Cell, "a"),
+                (Pipe, "|"),
+                (Pipe, "|"),
+            ],
+        );
+        assert_eq!(tree.to_string(), input);
+    }
+
+    #[test]
+    fn nodes() {
+        let input = "@tag\nFeature: Foo\n  Scenario: Bar\n    Given a step\n      | a |\n";
+        let tree = SyntaxTree::parse(input, GherkinEnv::default()).unwrap();
+
+        let node = |node: &SyntaxNode| {
+            node.children
+                .iter()
+                .filter_map(|c| match c {
+                    SyntaxElement::Node(n) => Some(n.clone()),
+                    SyntaxElement::Token(_) => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let root = tree.root();
+        assert_eq!(root.kind, NodeKind::Document);
+        let feature = &node(root)[0];
+        assert_eq!(feature.kind, NodeKind::Feature);
+        assert!(tree.text_of(feature.span).starts_with("@tag\nFeature"));
+        let step = &node(&node(feature)[0])[0];
+        assert_eq!(step.kind, NodeKind::Step);
+        assert_eq!(tree.text_of(step.span), "Given a step\n      | a |");
+        let table = &node(step)[0];
+        assert_eq!(table.kind, NodeKind::Table);
+        assert_eq!(node(table)[0].kind, NodeKind::TableRow);
+    }
+
+    #[test]
+    fn without_trailing_newline() {
+        let input = "Feature: Foo\n  Scenario: Bar\n    Given a step";
+        let tree = SyntaxTree::parse(input, GherkinEnv::default()).unwrap();
+        assert_eq!(tree.to_string(), input);
+        assert_eq!(tree.tokens().last().unwrap().kind, TokenKind::StepText);
+    }
+}
@@ -0,0 +1,345 @@
+// Copyright (c) 2026  Brendan Molloy <brendan@bbqsrc.net>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Keyword autocorrection of [`ParseMode::Forgiving`](crate::ParseMode::Forgiving).
+
+use std::fmt;
+
+use crate::{Background, Feature, LineCol, Scenario, Span, Step};
+
+/// A fix of a misspelled keyword, applied by
+/// [`ParseMode::Forgiving`](crate::ParseMode::Forgiving).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Correction {
+    /// The `(start, end)` offset of the corrected text in the source.
+    pub span: Span,
+    /// The `(line, col)` position of the corrected text in the source.
+    pub position: LineCol,
+    /// The text found in the source, like `scenario outline :`.
+    pub found: String,
+    /// The text it was corrected to, like `Scenario Outline:`.
+    pub replacement: String,
+}
+
+impl Correction {
+    /// Applies the `corrections` to the `source` they were found in.
+    pub fn fix(source: &str, corrections: &[Correction]) -> String {
+        let mut corrections = corrections.iter().collect::<Vec<_>>();
+        corrections.sort_by_key(|c| c.span.start);
+
+        let mut fixed = String::with_capacity(source.len());
+        let mut last = 0;
+        for c in corrections {
+            fixed.push_str(&source[last..c.span.start]);
+            fixed.push_str(&c.replacement);
+            last = c.span.end;
+        }
+        fixed.push_str(&source[last..]);
+        fixed
+    }
+}
+
+impl fmt::Display for Correction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({}:{}): `{}` -> `{}`",
+            self.position.line, self.position.col, self.found, self.replacement,
+        )
+    }
+}
+
+/// Matches the `keyword` at the start of the `input` ignoring case and amount
+/// of whitespace, returning the number of matched characters.
+///
+/// Trailing whitespace of a step keyword (like `Given `) may be replaced with
+/// a colon, which is left unmatched.
+pub(crate) fn match_keyword(input: &str, keyword: &str) -> Option<usize> {
+    let mut input = input.chars().peekable();
+    let mut keyword = keyword.chars().peekable();
+    let mut len = 0;
+
+    while let Some(k) = keyword.next() {
+        if !k.is_whitespace() {
+            if !chars_match(k, input.next()?) {
+                return None;
+            }
+            len += 1;
+        } else if keyword.peek().is_none() {
+            return match input.peek() {
+                Some(':') => Some(len),
+                Some(' ' | '\t') => Some(len + 1),
+                _ => None,
+            };
+        } else {
+            while keyword.next_if(|k| k.is_whitespace()).is_some() {}
+            let ws = std::iter::from_fn(|| input.next_if(|c| matches!(c, ' ' | '\t'))).count();
+            if ws == 0 {
+                return None;
+            }
+            len += ws;
+        }
+    }
+    Some(len)
+}
+
+fn chars_match(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+/// Collects the corrections made to the keywords of the `feature` parsed from
+/// the `source` in [`ParseMode::Forgiving`](crate::ParseMode::Forgiving).
+pub(crate) fn corrections(source: &str, feature: &Feature) -> Vec<Correction> {
+    let mut corrections = Corrections {
+        source,
+        found: vec![],
+    };
+
+    corrections.title(feature.span, &feature.keyword);
+    if let Some(background) = &feature.background {
+        corrections.background(background);
+    }
+    corrections.scenarios(&feature.scenarios);
+    for rule in &feature.rules {
+        corrections.title(rule.span, &rule.keyword);
+        if let Some(background) = &rule.background {
+            corrections.background(background);
+        }
+        corrections.scenarios(&rule.scenarios);
+    }
+
+    corrections.found
+}
+
+struct Corrections<'a> {
+    source: &'a str,
+    found: Vec<Correction>,
+}
+
+impl Corrections<'_> {
+    fn background(&mut self, background: &Background) {
+        self.title(background.span, &background.keyword);
+        self.steps(&background.steps);
+    }
+
+    fn scenarios(&mut self, scenarios: &[Scenario]) {
+        for scenario in scenarios {
+            self.title(scenario.span, &scenario.keyword);
+            self.steps(&scenario.steps);
+            for examples in &scenario.examples {
+                self.title(examples.span, &examples.keyword);
+            }
+        }
+    }
+
+    fn steps(&mut self, steps: &[Step]) {
+        for step in steps {
+            self.step(step);
+        }
+    }
+
+    /// Checks the title keyword, which is canonically followed by a colon.
+    fn title(&mut self, span: Span, keyword: &str) {
+        if let Some(start) = self.keyword_start(span.start) {
+            let end = start + self.keyword_len(start, keyword);
+            let end = end + colons_len(&self.source[end..]);
+            self.add(start, end, format!("{}:", keyword));
+        }
+    }
+
+    /// Checks the step keyword, which may be followed by a stray colon.
+    fn step(&mut self, step: &Step) {
+        if let Some(start) = self.keyword_start(step.span.start) {
+            let end = start + self.keyword_len(start, &step.keyword);
+            let rest = &self.source[end..];
+            let trimmed = rest.trim_start_matches([' ', '\t']);
+            let end = match trimmed.strip_prefix(':') {
+                Some(after) => self.source.len() - after.trim_start_matches([' ', '\t']).len(),
+                None => end,
+            };
+            self.add(start, end, step.keyword.clone());
+        }
+    }
+
+    /// Skips whitespace and comments preceding the keyword at `offset`.
+    fn keyword_start(&self, mut offset: usize) -> Option<usize> {
+        loop {
+            let rest = &self.source[offset..];
+            let trimmed = rest.trim_start();
+            offset += rest.len() - trimmed.len();
+            if !trimmed.starts_with('#') {
+                return (!trimmed.is_empty()).then_some(offset);
+            }
+            offset += trimmed.find('\n').unwrap_or(trimmed.len());
+        }
+    }
+
+    /// Returns the length in bytes of the `keyword` found at `start`.
+    fn keyword_len(&self, start: usize, keyword: &str) -> usize {
+        let rest = &self.source[start..];
+        let chars = match_keyword(rest, keyword).unwrap_or_default();
+        rest.char_indices()
+            .nth(chars)
+            .map_or(rest.len(), |(i, _)| i)
+    }
+
+    fn add(&mut self, start: usize, end: usize, replacement: String) {
+        let found = &self.source[start..end];
+        if found == replacement {
+            return;
+        }
+
+        let line_start = self.source[..start].rfind('\n').map_or(0, |i| i + 1);
+        self.found.push(Correction {
+            span: Span { start, end },
+            position: LineCol {
+                line: self.source[..start].matches('\n').count() + 1,
+                col: self.source[line_start..start].chars().count() + 1,
+            },
+            found: found.into(),
+            replacement,
+        });
+    }
+}
+
+/// Returns the length in bytes of the colons, possibly separated by
+/// whitespace, at the start of the `input`.
+fn colons_len(input: &str) -> usize {
+    let mut len = 0;
+    loop {
+        let rest = &input[len..];
+        let trimmed = rest.trim_start_matches([' ', '\t']);
+        match trimmed.strip_prefix(':') {
+            Some(after) => len = input.len() - after.len(),
+            None => return len,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::{Feature, GherkinEnv, ParseMode};
+
+    use super::*;
+
+    const INPUT: &str = r#"feature Foo
+  A description
+
+  background :
+    given: a thing
+
+  # A comment.
+  scenario   outline:: Bar
+    WHEN   I do <this>
+    then it works
+
+    examples:
+      | this |
+      | that |
+"#;
+
+    #[test]
+    fn keywords_are_corrected() {
+        let (feature, corrections) =
+            Feature::parse_forgiving(INPUT, GherkinEnv::default()).unwrap();
+
+        assert_eq!(feature.keyword, "Feature");
+        assert_eq!(feature.name, "Foo");
+        assert_eq!(feature.description.unwrap(), "  A description");
+        let background = feature.background.as_ref().unwrap();
+        assert_eq!(background.keyword, "Background");
+        assert_eq!(background.steps[0].keyword, "Given ");
+        assert_eq!(background.steps[0].value, "a thing");
+        let scenario = &feature.scenarios[0];
+        assert_eq!(scenario.keyword, "Scenario Outline");
+        assert_eq!(scenario.name, "Bar");
+        assert_eq!(scenario.steps[0].keyword, "When ");
+        assert_eq!(scenario.steps[0].value, "I do <this>");
+        assert_eq!(scenario.examples[0].keyword, "Examples");
+
+        assert_eq!(
+            corrections
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "(1:1): `feature` -> `Feature:`",
+                "(4:3): `background :` -> `Background:`",
+                "(5:5): `given: ` -> `Given `",
+                "(8:3): `scenario   outline::` -> `Scenario Outline:`",
+                "(9:5): `WHEN ` -> `When `",
+                "(10:5): `then ` -> `Then `",
+                "(12:5): `examples:` -> `Examples:`",
+            ],
+        );
+    }
+
+    #[test]
+    fn corrections_fix_source() {
+        let (_, corrections) = Feature::parse_forgiving(INPUT, GherkinEnv::default()).unwrap();
+        let fixed = Correction::fix(INPUT, &corrections);
+
+        let feature = Feature::parse(&fixed, GherkinEnv::default().with_mode(ParseMode::Strict));
+        assert!(feature.is_ok(), "{}", fixed);
+        assert_eq!(
+            Feature::parse_forgiving(&fixed, GherkinEnv::default())
+                .unwrap()
+                .1,
+            [],
+        );
+    }
+
+    #[test]
+    fn exact_keywords_are_not_corrected() {
+        let input = "Feature: Foo\n\n  Scenario: Bar\n    Given  a thing\n";
+        let (feature, corrections) =
+            Feature::parse_forgiving(input, GherkinEnv::default()).unwrap();
+
+        assert_eq!(feature.scenarios[0].steps[0].value, "a thing");
+        assert!(corrections.is_empty());
+    }
+
+    #[test]
+    fn keywords_are_exact_in_other_modes() {
+        assert!(Feature::parse("feature: Foo\n", GherkinEnv::default()).is_err());
+        assert!(Feature::parse("Feature Foo\n", GherkinEnv::default()).is_err());
+    }
+
+    #[test]
+    fn matches_keyword() {
+        assert_eq!(
+            match_keyword("scenario  OUTLINE: x", "Scenario Outline"),
+            Some(17)
+        );
+        assert_eq!(match_keyword("given: x", "Given "), Some(5));
+        assert_eq!(match_keyword("Given x", "Given "), Some(6));
+        assert_eq!(match_keyword("Givenx", "Given "), None);
+        assert_eq!(match_keyword("Given\n", "Given "), None);
+        assert_eq!(match_keyword("ScenarioOutline", "Scenario Outline"), None);
+    }
+
+    #[test]
+    fn fixture_good() {
+        let d = env!("CARGO_MANIFEST_DIR");
+        let files = fs::read_dir(format!("{}/tests/fixtures/data/good/", d)).unwrap();
+        for file in files {
+            let path = file.unwrap().path();
+            if path.extension().is_some_and(|ext| ext == "feature") {
+                let input = fs::read_to_string(&path).unwrap();
+                if let Ok(expected) = Feature::parse(&input, GherkinEnv::default()) {
+                    let (feature, corrections) =
+                        Feature::parse_forgiving(&input, GherkinEnv::default()).unwrap();
+                    assert_eq!(feature, expected, "{:?}", path);
+                    assert_eq!(corrections, [], "{:?}", path);
+                }
+            }
+        }
+    }
+}
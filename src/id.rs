@@ -0,0 +1,142 @@
+// Copyright (c) 2026  Brendan Molloy <brendan@bbqsrc.net>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Generating the ids of the parsed nodes.
+
+use std::fmt;
+
+/// Generator of the ids of the parsed nodes, set via
+/// [`GherkinEnv::with_id_generator()`](crate::GherkinEnv::with_id_generator).
+///
+/// The ids are generated in the order upstream does, so the ones of the
+/// [`IncrementingIdGenerator`] match the ids of the upstream AST: every node
+/// gets its id after its children and tags.
+pub trait IdGenerator: fmt::Debug + Send {
+    /// Starts generating the ids of the nodes parsed from the `input`.
+    ///
+    /// Does nothing by default.
+    fn start(&mut self, input: &str) {
+        let _ = input;
+    }
+
+    /// Returns the id of the next node.
+    fn next_id(&mut self) -> String;
+}
+
+/// [`IdGenerator`] numbering the nodes of every input from `0`, like the
+/// `incrementing` one of upstream. Used by default.
+///
+/// ```
+/// # use gherkin::{IdGenerator, IncrementingIdGenerator};
+/// #
+/// let mut ids = IncrementingIdGenerator::default();
+/// ids.start("Feature: Foo\n");
+/// assert_eq!(ids.next_id(), "0");
+/// assert_eq!(ids.next_id(), "1");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IncrementingIdGenerator {
+    next: usize,
+}
+
+impl IdGenerator for IncrementingIdGenerator {
+    fn start(&mut self, _: &str) {
+        self.next = 0;
+    }
+
+    fn next_id(&mut self) -> String {
+        let id = self.next.to_string();
+        self.next += 1;
+        id
+    }
+}
+
+/// [`IdGenerator`] giving the nodes version 4 UUIDs, like the `uuid` one of
+/// upstream.
+///
+/// Unlike upstream, the UUIDs aren't random but derived from the input, so
+/// they are the same for every parse of it, while differing between inputs.
+///
+/// ```
+/// # use gherkin::{IdGenerator, UuidIdGenerator};
+/// #
+/// let mut ids = UuidIdGenerator::default();
+/// ids.start("Feature: Foo\n");
+/// let first = ids.next_id();
+/// assert_eq!(first.len(), 36);
+/// assert_ne!(ids.next_id(), first);
+///
+/// ids.start("Feature: Foo\n");
+/// assert_eq!(ids.next_id(), first);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UuidIdGenerator {
+    state: u64,
+}
+
+impl UuidIdGenerator {
+    /// Advances the `splitmix64` sequence seeded by the input.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+impl IdGenerator for UuidIdGenerator {
+    fn start(&mut self, input: &str) {
+        // FNV-1a, as the `std` hashers aren't guaranteed to be stable.
+        self.state = input.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+            (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+        });
+    }
+
+    fn next_id(&mut self) -> String {
+        let bits = (u128::from(self.next_u64()) << 64) | u128::from(self.next_u64());
+        // Sets the version to 4 and the variant to RFC 4122.
+        let bits = (bits & !(0xf << 76) & !(0x3 << 62)) | (0x4 << 76) | (0x2 << 62);
+        let hex = format!("{:032x}", bits);
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..],
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn uuids_are_version_4() {
+        let mut ids = UuidIdGenerator::default();
+        ids.start("Feature: Foo\n");
+        for _ in 0..10 {
+            let id = ids.next_id();
+            let groups = id.split('-').map(str::len).collect::<Vec<_>>();
+            assert_eq!(groups, [8, 4, 4, 4, 12]);
+            assert_eq!(&id[14..15], "4");
+            assert!("89ab".contains(&id[19..20]), "{}", id);
+        }
+    }
+
+    #[test]
+    fn uuids_differ_between_inputs() {
+        let mut ids = UuidIdGenerator::default();
+        ids.start("Feature: Foo\n");
+        let foo = ids.next_id();
+        ids.start("Feature: Bar\n");
+        assert_ne!(ids.next_id(), foo);
+    }
+}
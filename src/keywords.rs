@@ -6,7 +6,10 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use std::ops::Deref;
+use std::{cmp::Reverse, ops::Deref};
+
+/// Languages defined in `languages.json`.
+pub(crate) const LANGUAGES: &[&str] = &include!(concat!(env!("OUT_DIR"), "/languages.gen.rs"));
 
 #[derive(Debug, Clone)]
 pub(crate) struct Keywords<'a> {
@@ -60,75 +63,65 @@ impl<'a> Keywords<'a> {
         v
     }
 
+    /// Title keywords ending the description of a `Feature`.
     pub fn excluded_feature(&'a self) -> Vec<&'a str> {
-        [
-            self.background,
-            self.rule,
-            self.scenario,
-            self.scenario_outline,
-        ]
-        .concat()
+        longest_first(
+            [
+                self.background,
+                self.rule,
+                self.scenario,
+                self.scenario_outline,
+            ]
+            .concat(),
+        )
     }
 
+    /// Title keywords ending the description of a `Rule`.
     pub fn excluded_rule(&'a self) -> Vec<&'a str> {
-        [self.background, self.scenario, self.scenario_outline].concat()
+        self.excluded_feature()
     }
 
+    /// Title keywords ending the description of a `Background`.
     pub fn excluded_background(&'a self) -> Vec<&'a str> {
-        [
-            self.scenario,
-            self.scenario_outline,
-            self.given,
-            self.when,
-            self.then,
-            self.and,
-            self.but,
-        ]
-        .concat()
+        longest_first([self.rule, self.scenario, self.scenario_outline].concat())
     }
 
+    /// Title keywords ending the description of a `Scenario`.
     pub fn excluded_scenario(&'a self) -> Vec<&'a str> {
-        [
-            self.scenario,
-            self.scenario_outline,
-            self.given,
-            self.when,
-            self.then,
-            self.and,
-            self.but,
-        ]
-        .concat()
+        longest_first(
+            [
+                self.rule,
+                self.scenario,
+                self.scenario_outline,
+                self.examples,
+            ]
+            .concat(),
+        )
     }
 
+    /// Title keywords ending the description of a `Scenario Outline`.
     pub fn excluded_scenario_outline(&'a self) -> Vec<&'a str> {
-        [
-            self.scenario,
-            self.scenario_outline,
-            self.given,
-            self.when,
-            self.then,
-            self.and,
-            self.but,
-        ]
-        .concat()
+        self.excluded_scenario()
     }
 
+    /// Title keywords ending the description of `Examples`.
     pub fn excluded_examples(&'a self) -> Vec<&'a str> {
-        let mut r = [
-            self.scenario,
-            self.scenario_outline,
-            self.given,
-            self.when,
-            self.then,
-            self.and,
-            self.but,
-        ]
-        .concat();
-        r.push("|");
-        r
+        self.excluded_scenario()
+    }
+
+    /// All the step keywords.
+    pub fn steps(&self) -> Vec<&'a str> {
+        [self.given, self.when, self.then, self.and, self.but].concat()
     }
 }
 
+/// Sorts the `keywords` so that none of them is matched instead of a longer
+/// one it's a prefix of (like `Example` instead of `Examples`).
+fn longest_first(mut keywords: Vec<&str>) -> Vec<&str> {
+    keywords.sort_by_key(|k| Reverse(k.chars().count()));
+    keywords
+}
+
 impl Default for Keywords<'_> {
     fn default() -> Self {
         EN
@@ -0,0 +1,325 @@
+// Copyright (c) 2026  Brendan Molloy <brendan@bbqsrc.net>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Choosing the language of a `.feature` file without a `# language:`
+//! directive.
+
+use std::path::{Component, Path};
+
+use crate::{
+    keywords::{Keywords, LANGUAGES},
+    EnvError,
+};
+
+/// Number of keyword line candidates looked at by [`detect()`].
+const DETECTION_LINES: usize = 20;
+
+/// Policy choosing the language of a `.feature` file which has no
+/// `# language:` directive, set via
+/// [`GherkinEnv::with_language_policy()`](crate::GherkinEnv::with_language_policy).
+///
+/// The language is taken from the first path pattern the file matches, or
+/// detected from its keywords if enabled. Otherwise, the language of the
+/// [`GherkinEnv`](crate::GherkinEnv) is used.
+///
+/// ```
+/// # use gherkin::LanguagePolicy;
+/// #
+/// let policy = LanguagePolicy::new()
+///     .path("features/de/**", "de")
+///     .unwrap()
+///     .detect(true);
+///
+/// let input = "Feature: Foo\n  Scenario: Bar\n    Given a step\n";
+/// assert_eq!(policy.language_of(Some("features/de/foo.feature".as_ref()), input), Some("de"));
+/// assert_eq!(policy.language_of(Some("features/foo.feature".as_ref()), input), Some("en"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LanguagePolicy {
+    paths: Vec<(String, String)>,
+    detect: bool,
+}
+
+impl LanguagePolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses the `language` for files matching the `glob`.
+    ///
+    /// `*` matches any part of a path component, `?` matches any single
+    /// character of it, and `**` matches any number of path components. A
+    /// `glob` not starting with `/` may match at any directory level, so
+    /// `features/de/**` matches `/repo/features/de/foo.feature`.
+    pub fn path<G: Into<String>>(mut self, glob: G, language: &str) -> Result<Self, EnvError> {
+        if Keywords::get(language).is_none() {
+            return Err(EnvError::UnsupportedLanguage(language.into()));
+        }
+
+        self.paths.push((glob.into(), language.into()));
+        Ok(self)
+    }
+
+    /// Sets whether the language is detected from the keywords used in the
+    /// file, when none of the path patterns matches.
+    pub fn detect(mut self, detect: bool) -> Self {
+        self.detect = detect;
+        self
+    }
+
+    /// Returns the language for the `input` read from the `path`, if known,
+    /// unless it has a `# language:` directive or the policy doesn't apply.
+    pub fn language_of(&self, path: Option<&Path>, input: &str) -> Option<&str> {
+        if has_directive(input) {
+            return None;
+        }
+
+        path.and_then(|path| {
+            self.paths
+                .iter()
+                .find(|(glob, _)| matches_path(glob, path))
+                .map(|(_, language)| language.as_str())
+        })
+        .or_else(|| self.detect.then(|| detect(input)).flatten())
+    }
+}
+
+/// Detects the language of the `input` by scoring its first keyword lines
+/// against every language, preferring English on a tie.
+pub(crate) fn detect(input: &str) -> Option<&'static str> {
+    let lines = candidate_lines(input);
+
+    let mut best = None;
+    for language in std::iter::once(&"en").chain(LANGUAGES) {
+        let keywords = Keywords::get(language).unwrap();
+        let score = lines
+            .iter()
+            .filter(|l| is_keyword_line(l, &keywords))
+            .count();
+        if score > best.map_or(0, |(_, s)| s) {
+            best = Some((*language, score));
+        }
+    }
+    best.map(|(language, _)| language)
+}
+
+/// Returns the first lines of the `input` which may start with a keyword,
+/// skipping blank lines, comments, tags, tables and docstrings, and stripping
+/// Markdown heading and bullet markers.
+fn candidate_lines(input: &str) -> Vec<&str> {
+    let mut docstring = None;
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            let separator = ["\"\"\"", "```"].iter().find(|s| line.starts_with(**s));
+            match (docstring, separator) {
+                (None, Some(s)) => docstring = Some(*s),
+                (Some(d), Some(s)) if d == *s => docstring = None,
+                _ => {}
+            }
+            docstring.is_none() && separator.is_none()
+        })
+        .map(|line| {
+            let heading = line.trim_start_matches('#');
+            if heading.len() < line.len() && heading.starts_with([' ', '\t']) {
+                heading.trim_start()
+            } else {
+                line.strip_prefix(['*', '+', '-'])
+                    .map_or(line, str::trim_start)
+            }
+        })
+        .filter(|line| !line.is_empty() && !line.starts_with(['#', '@', '|', '`']))
+        .take(DETECTION_LINES)
+        .collect()
+}
+
+fn is_keyword_line(line: &str, keywords: &Keywords<'_>) -> bool {
+    let titles = [
+        keywords.feature,
+        keywords.background,
+        keywords.rule,
+        keywords.scenario,
+        keywords.scenario_outline,
+        keywords.examples,
+    ];
+    let title = titles
+        .iter()
+        .flat_map(|k| k.iter())
+        .any(|k| line.strip_prefix(k).is_some_and(|l| l.starts_with(':')));
+    let step = keywords
+        .steps()
+        .iter()
+        .filter(|k| **k != "* ")
+        .any(|k| line.starts_with(k));
+
+    title || step
+}
+
+/// Checks whether the `input` has a `# language:` directive before its first
+/// non-comment line.
+fn has_directive(input: &str) -> bool {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .take_while(|l| l.starts_with('#'))
+        .any(|l| {
+            let l = l.trim_start_matches('#').trim_start();
+            l.strip_prefix("language")
+                .is_some_and(|l| l.trim_start().starts_with(':'))
+        })
+}
+
+/// Checks whether the `path` matches the `glob`.
+fn matches_path(glob: &str, path: &Path) -> bool {
+    let path = path
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(c) => Some(c.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let path = path.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+    let components = glob
+        .split('/')
+        .filter(|c| !c.is_empty())
+        .collect::<Vec<_>>();
+
+    if glob.starts_with('/') {
+        matches_components(&components, &path)
+    } else {
+        (0..path.len()).any(|start| matches_components(&components, &path[start..]))
+    }
+}
+
+fn matches_components(glob: &[&str], path: &[&str]) -> bool {
+    match glob.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| matches_components(rest, &path[skip..])),
+        Some((first, rest)) => path
+            .split_first()
+            .is_some_and(|(c, path)| matches_component(first, c) && matches_components(rest, path)),
+    }
+}
+
+fn matches_component(glob: &str, component: &str) -> bool {
+    fn matches(glob: &[char], component: &[char]) -> bool {
+        match glob.split_first() {
+            None => component.is_empty(),
+            Some(('*', rest)) => {
+                (0..=component.len()).any(|skip| matches(rest, &component[skip..]))
+            }
+            Some((g, rest)) => component
+                .split_first()
+                .is_some_and(|(c, component)| (*g == '?' || g == c) && matches(rest, component)),
+        }
+    }
+
+    let glob = glob.chars().collect::<Vec<_>>();
+    let component = component.chars().collect::<Vec<_>>();
+    matches(&glob, &component)
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+    use crate::{Feature, GherkinEnv};
+
+    #[test]
+    fn detects_fixture_languages() {
+        let d = env!("CARGO_MANIFEST_DIR");
+        let files = fs::read_dir(format!("{}/tests/fixtures/data/good/", d)).unwrap();
+        for file in files {
+            let path = file.unwrap().path();
+            if path.extension().is_some_and(|ext| ext == "feature") {
+                let input = fs::read_to_string(&path).unwrap();
+                let directive = input
+                    .lines()
+                    .find_map(|l| l.trim().strip_prefix("# language:"))
+                    .map(str::trim);
+                if let Some(language) = directive {
+                    assert_eq!(detect(&input), Some(language), "{:?}", path);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn detects_every_language() {
+        for language in LANGUAGES {
+            let k = Keywords::get(language).unwrap();
+            let step = |keywords: &[&'static str]| *keywords.iter().find(|k| **k != "* ").unwrap();
+            let input = format!(
+                "{}: Foo\n  {}: Bar\n    {}a\n    {}b\n    {}c\n",
+                k.feature[0],
+                k.scenario[0],
+                step(k.given),
+                step(k.when),
+                step(k.then),
+            );
+
+            // Dialects may share all the used keywords, like `en` and `en-au`.
+            let detected = Keywords::get(detect(&input).unwrap()).unwrap();
+            assert!(
+                candidate_lines(&input)
+                    .iter()
+                    .all(|l| is_keyword_line(l, &detected)),
+                "{}",
+                language,
+            );
+        }
+    }
+
+    #[test]
+    fn detects_markdown_language() {
+        let input =
+            "# Fonctionnalité: Foo\n\n## Scénario: Bar\n\n* Soit un pas\n* Alors ça marche\n";
+        assert_eq!(detect(input), Some("fr"));
+    }
+
+    #[test]
+    fn detects_nothing_in_prose() {
+        assert_eq!(detect("Some prose.\nMore prose.\n"), None);
+    }
+
+    #[test]
+    fn matches_globs() {
+        let path = Path::new("/repo/features/de/login/foo.feature");
+        assert!(matches_path("features/de/**", path));
+        assert!(matches_path("de/**/*.feature", path));
+        assert!(matches_path("/repo/features/*/login/f?o.feature", path));
+        assert!(matches_path("**/foo.feature", path));
+        assert!(!matches_path("/features/de/**", path));
+        assert!(!matches_path("features/fr/**", path));
+        assert!(!matches_path("features/de/*.feature", path));
+    }
+
+    #[test]
+    fn directive_wins() {
+        let policy = LanguagePolicy::new().path("**", "de").unwrap().detect(true);
+        let input = "# language: fr\nFonctionnalité: Foo\n";
+        assert_eq!(
+            policy.language_of(Some(Path::new("foo.feature")), input),
+            None
+        );
+        assert!(LanguagePolicy::new().path("**", "xx").is_err());
+    }
+
+    #[test]
+    fn env_uses_policy() {
+        let input = "Funktionalität: Foo\n  Szenario: Bar\n    Angenommen ein Schritt\n";
+        assert!(Feature::parse(input, GherkinEnv::default()).is_err());
+
+        let env = GherkinEnv::default().with_language_policy(LanguagePolicy::new().detect(true));
+        let feature = Feature::parse(input, env).unwrap();
+        assert_eq!(feature.scenarios[0].steps[0].keyword, "Angenommen ");
+    }
+}
@@ -30,8 +30,9 @@
 //!
 //! ### Unparsed elements
 //!
-//! Indentation and comments are ignored by the parser. Most other things can be accessed via
-//! properties of the relevant struct.
+//! Indentation is ignored by the parser. Comments are collected in
+//! [`GherkinDocument::comments`]. Most other things can be accessed via properties of the relevant
+//! struct.
 
 #![doc(
     html_logo_url = "https://avatars.githubusercontent.com/u/91469139?s=128",
@@ -41,17 +42,35 @@
 #![forbid(non_ascii_idents, unsafe_code)]
 #![warn(clippy::allow_attributes, clippy::allow_attributes_without_reason)]
 
+#[cfg(feature = "parser")]
+pub mod cst;
+#[cfg(feature = "parser")]
+mod forgiving;
+#[cfg(feature = "parser")]
+mod id;
 #[cfg(feature = "parser")]
 mod keywords;
 #[cfg(feature = "parser")]
+mod language;
+mod locate;
+#[cfg(feature = "parser")]
+mod markdown;
+mod outline;
+#[cfg(feature = "parser")]
 mod parser;
 #[cfg(feature = "parser")]
+pub mod pickle;
+#[cfg(feature = "parser")]
+mod strict;
+#[cfg(feature = "parser")]
 pub mod tagexpr;
+pub mod visit;
+pub mod visit_mut;
 
 #[cfg(feature = "parser")]
 use std::path::Path;
 use std::{
-    collections::HashSet,
+    collections::HashMap,
     fmt::{self, Display},
     path::PathBuf,
 };
@@ -62,13 +81,30 @@ use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
 #[cfg(feature = "parser")]
-pub use self::parser::{EnvError, GherkinEnv};
+pub use self::{
+    forgiving::Correction,
+    id::{IdGenerator, IncrementingIdGenerator, UuidIdGenerator},
+    language::LanguagePolicy,
+    parser::{EnvError, GherkinEnv, ParseMode},
+    strict::StrictError,
+};
+pub use self::{
+    locate::{Location, NodeRef},
+    outline::{OutlineIssue, Placeholder},
+};
 
 #[cfg(feature = "parser")]
 pub fn is_language_supported(lang: &str) -> bool {
     keywords::Keywords::get(lang).is_some()
 }
 
+/// Detects the language of a `.feature` file `input` from the keywords used
+/// in it, regardless of its `# language:` directive.
+#[cfg(feature = "parser")]
+pub fn detect_language(input: &str) -> Option<&'static str> {
+    language::detect(input)
+}
+
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
 pub struct Span {
@@ -119,10 +155,18 @@ pub struct Background {
     pub name: String,
     /// The description of the background, if found.
     #[cfg_attr(feature = "parser", builder(default))]
-    pub description: Option<String>,
+    pub description: Option<Description>,
     /// The parsed steps from the background directive.
     pub steps: Vec<Step>,
-    /// The `(start, end)` offset the background directive was found in the .feature file.
+    /// The id of the background, unique within the parsed .feature file.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub id: String,
+    /// The `(start, end)` offset the header line of the background was found in the .feature file,
+    /// from its keyword to the end of its name, without the trailing whitespace.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub header_span: Span,
+    /// The `(start, end)` offset the background was found in the .feature file, from its keyword to
+    /// the end of its last line, including its description and steps.
     #[cfg_attr(feature = "parser", builder(default))]
     pub span: Span,
     /// The `(line, col)` position the background directive was found in the .feature file.
@@ -130,6 +174,111 @@ pub struct Background {
     pub position: LineCol,
 }
 
+#[cfg(feature = "parser")]
+impl Background {
+    /// Parses a single `Background` along with its steps from the `input`, in the language of the
+    /// `env` or the one set by a `# language:` directive.
+    pub fn parse<S: AsRef<str>>(input: S, env: GherkinEnv) -> Result<Background, ParseError> {
+        parse_with(input.as_ref(), None, &env, |input, env| {
+            parser::gherkin_parser::background_fragment(input, env).map_err(ParseError::from_peg)
+        })
+    }
+}
+
+/// A comment on its own line
+#[cfg_attr(feature = "parser", derive(TypedBuilder))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "juniper", derive(juniper::GraphQLObject))]
+#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+pub struct Comment {
+    /// The whole line of the comment, including its indentation.
+    pub text: String,
+    /// The `(start, end)` offset the comment line was found in the .feature file.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub span: Span,
+    /// The `(line, col)` position the comment line was found in the .feature file.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub position: LineCol,
+}
+
+/// A description of a feature, rule, background, scenario or examples
+#[cfg_attr(feature = "parser", derive(TypedBuilder))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "juniper", derive(juniper::GraphQLObject))]
+#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+pub struct Description {
+    /// The lines of the description as written, including their indentation and the empty lines
+    /// between them, but without the comment lines.
+    pub text: String,
+    /// The `(start, end)` offset the description was found in the .feature file, from the start
+    /// of its first line to the end of its last one.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub span: Span,
+    /// The `(line, col)` position the first line of the description was found in the .feature
+    /// file.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub position: LineCol,
+}
+
+impl AsRef<str> for Description {
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl PartialEq<str> for Description {
+    fn eq(&self, other: &str) -> bool {
+        self.text == other
+    }
+}
+
+impl PartialEq<&str> for Description {
+    fn eq(&self, other: &&str) -> bool {
+        self.text == *other
+    }
+}
+
+impl Display for Description {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.text.fmt(f)
+    }
+}
+
+/// A docstring of a step
+#[cfg_attr(feature = "parser", derive(TypedBuilder))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "juniper", derive(juniper::GraphQLObject))]
+#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+pub struct DocString {
+    /// The content of the docstring, stripped of the indentation of its opening delimiter.
+    pub content: String,
+    /// The delimiter the docstring is wrapped in, like `"""` or ```` ``` ````.
+    pub delimiter: String,
+    /// The media type following the opening delimiter, like `json` in `"""json`, if any.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub media_type: Option<String>,
+    /// The `(start, end)` offset the media type was found in the .feature file, if any.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub media_type_span: Option<Span>,
+    /// The `(start, end)` offset the docstring was found in the .feature file, from its opening
+    /// delimiter to its closing one.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub span: Span,
+    /// The `(start, end)` offsets the lines of the content were found in the .feature file, one
+    /// per line, without the stripped indentation and the line break.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub line_spans: Vec<Span>,
+    /// The `(line, col)` position the opening delimiter was found in the .feature file.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub position: LineCol,
+}
+
+impl AsRef<str> for DocString {
+    fn as_ref(&self) -> &str {
+        &self.content
+    }
+}
+
 /// Examples for a scenario
 #[cfg_attr(feature = "parser", derive(TypedBuilder))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -142,13 +291,21 @@ pub struct Examples {
     pub name: Option<String>,
     /// The description of the examples, if found.
     #[cfg_attr(feature = "parser", builder(default))]
-    pub description: Option<String>,
+    pub description: Option<Description>,
     /// The data table from the examples directive.
     pub table: Option<Table>,
     /// The tags for the examples directive if provided.
     #[cfg_attr(feature = "parser", builder(default))]
-    pub tags: Vec<String>,
-    /// The `(start, end)` offset the examples directive was found in the .feature file.
+    pub tags: Vec<Tag>,
+    /// The id of the examples, unique within the parsed .feature file.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub id: String,
+    /// The `(start, end)` offset the header line of the examples was found in the .feature file,
+    /// from its keyword to the end of its name, without the trailing whitespace.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub header_span: Span,
+    /// The `(start, end)` offset the examples was found in the .feature file, from its keyword to
+    /// the end of its last line, including its description and table, but not its tags.
     #[cfg_attr(feature = "parser", builder(default))]
     pub span: Span,
     /// The `(line, col)` position the examples directive was found in the .feature file.
@@ -156,6 +313,70 @@ pub struct Examples {
     pub position: LineCol,
 }
 
+impl Examples {
+    /// Parses a single `Examples` block along with its table from the `input`, in the language of
+    /// the `env` or the one set by a `# language:` directive.
+    #[cfg(feature = "parser")]
+    pub fn parse<S: AsRef<str>>(input: S, env: GherkinEnv) -> Result<Examples, ParseError> {
+        parse_with(input.as_ref(), None, &env, |input, env| {
+            parser::gherkin_parser::examples_fragment(input, env).map_err(ParseError::from_peg)
+        })
+    }
+
+    /// Returns the names of the tags of the examples, without the leading `@`.
+    pub fn tag_names(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(|t| t.name.as_str())
+    }
+
+    /// Returns the header row of the table of the examples, naming the placeholders its columns
+    /// are the values of.
+    pub fn header(&self) -> Option<&TableRow> {
+        self.table.as_ref().and_then(Table::header)
+    }
+
+    /// Returns the names of the columns of the table of the examples.
+    pub fn header_names(&self) -> impl Iterator<Item = &str> {
+        self.header()
+            .into_iter()
+            .flat_map(|h| &h.cells)
+            .map(|c| c.value.as_str())
+    }
+
+    /// Returns the rows of the table of the examples following the header, one per run of the
+    /// scenario outline.
+    pub fn body(&self) -> &[TableRow] {
+        self.table.as_ref().map_or(&[], Table::body)
+    }
+
+    /// Returns the values of every body row of the table of the examples, mapped by the names of
+    /// their columns.
+    ///
+    /// If several columns have the same name, the value of the first one is kept, as it's the one
+    /// replacing the placeholder.
+    ///
+    /// ```
+    /// # use gherkin::{Examples, GherkinEnv};
+    /// #
+    /// let input = "Examples:\n  | fruit | count |\n  | apple | 3     |\n";
+    /// let examples = Examples::parse(input, GherkinEnv::default()).unwrap();
+    ///
+    /// assert_eq!(examples.header_names().collect::<Vec<_>>(), ["fruit", "count"]);
+    /// let rows = examples.body_maps().collect::<Vec<_>>();
+    /// assert_eq!(rows[0]["fruit"], "apple");
+    /// assert_eq!(rows[0]["count"], "3");
+    /// ```
+    pub fn body_maps(&self) -> impl Iterator<Item = HashMap<&str, &str>> {
+        let names = self.header_names().collect::<Vec<_>>();
+        self.body().iter().map(move |row| {
+            let mut values = HashMap::new();
+            for (name, cell) in names.iter().zip(&row.cells) {
+                values.entry(*name).or_insert(cell.value.as_str());
+            }
+            values
+        })
+    }
+}
+
 /// A feature
 #[cfg_attr(feature = "parser", derive(TypedBuilder))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -168,7 +389,7 @@ pub struct Feature {
     pub name: String,
     /// The description of the feature, if found.
     #[cfg_attr(feature = "parser", builder(default))]
-    pub description: Option<String>,
+    pub description: Option<Description>,
     /// The background of the feature, if found.
     #[cfg_attr(feature = "parser", builder(default))]
     pub background: Option<Background>,
@@ -180,8 +401,20 @@ pub struct Feature {
     pub rules: Vec<Rule>,
     /// The tags for the feature if provided.
     #[cfg_attr(feature = "parser", builder(default))]
-    pub tags: Vec<String>,
-    /// The `(start, end)` offset the feature directive was found in the .feature file.
+    pub tags: Vec<Tag>,
+    /// The code of the language the feature was parsed in, like `en`.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub language: String,
+    /// The id of the feature, unique within the parsed .feature file.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub id: String,
+    /// The `(start, end)` offset the header line of the feature was found in the .feature file,
+    /// from its keyword to the end of its name, without the trailing whitespace.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub header_span: Span,
+    /// The `(start, end)` offset the feature was found in the .feature file, from its keyword to
+    /// the end of its last line, including its description, background, scenarios and rules, but
+    /// not its tags.
     #[cfg_attr(feature = "parser", builder(default))]
     pub span: Span,
     /// The `(line, col)` position the feature directive was found in the .feature file.
@@ -195,57 +428,242 @@ pub struct Feature {
 
 #[cfg(feature = "parser")]
 impl Feature {
+    /// Parses the .feature file at the `path`, or the Markdown with Gherkin one if it ends with
+    /// `.md`, failing if it has no feature.
     #[inline]
     pub fn parse_path<P: AsRef<Path>>(path: P, env: GherkinEnv) -> Result<Feature, ParseFileError> {
-        let mut s =
-            std::fs::read_to_string(path.as_ref()).map_err(|e| ParseFileError::Reading {
-                path: path.as_ref().to_path_buf(),
-                source: e,
-            })?;
-
-        if !s.ends_with('\n') {
-            // Add a new line at the end, because our parser is bad and we should feel bad.
-            s.push('\n');
-        }
-
-        let mut feature =
-            parser::gherkin_parser::feature(&s, &env).map_err(|e| ParseFileError::Parsing {
-                path: path.as_ref().to_path_buf(),
-                error: env
-                    .fatal_error
-                    .borrow_mut()
-                    .take()
-                    .or_else(|| env.last_error.borrow_mut().take()),
-                source: ParseError {
-                    position: LineCol {
-                        line: e.location.line,
-                        col: e.location.column,
-                    },
-                    expected: e.expected.tokens().collect(),
-                },
-            })?;
-
-        feature.path = Some(path.as_ref().to_path_buf());
+        let path = path.as_ref();
+        let mut feature = parse_path_with(path, &env, parse_feature, parse_markdown_feature)?;
+        feature.path = Some(path.to_path_buf());
         Ok(feature)
     }
 
+    /// Parses the `input`, failing if it has no feature.
     #[inline]
     pub fn parse<S: AsRef<str>>(input: S, env: GherkinEnv) -> Result<Feature, ParseError> {
-        use std::borrow::Cow;
-
-        let input: Cow<'_, str> = match input.as_ref().ends_with('\n') {
-            true => Cow::Borrowed(input.as_ref()),
-            // Add a new line at the end, because our parser is bad and we should feel bad.
-            false => Cow::Owned(format!("{}\n", input.as_ref())),
-        };
-        parser::gherkin_parser::feature(&input, &env).map_err(|e| ParseError {
-            position: LineCol {
-                line: e.location.line,
-                col: e.location.column,
-            },
-            expected: e.expected.tokens().collect(),
-        })
+        parse_with(input.as_ref(), None, &env, parse_feature)
     }
+
+    /// Parses the `input` as Markdown with Gherkin, like a `.feature.md` file.
+    ///
+    /// Headings (`# Feature:`, `## Scenario:`), bullets (`* Given ...`),
+    /// backticked tags and tables indented by 2 to 5 spaces form the
+    /// [`Feature`], while the other prose is ignored. [`Span`]s point into
+    /// the Markdown `input`.
+    pub fn parse_markdown<S: AsRef<str>>(input: S, env: GherkinEnv) -> Result<Feature, ParseError> {
+        parse_with(input.as_ref(), None, &env, parse_markdown_feature)
+    }
+
+    /// Parses the `input` in [`ParseMode::Forgiving`], returning the
+    /// [`Feature`] along with the [`Correction`]s applied to its keywords.
+    pub fn parse_forgiving<S: AsRef<str>>(
+        input: S,
+        env: GherkinEnv,
+    ) -> Result<(Feature, Vec<Correction>), ParseError> {
+        let input = input.as_ref();
+        let feature = Feature::parse(input, env.with_mode(ParseMode::Forgiving))?;
+        let corrections = forgiving::corrections(input, &feature);
+        Ok((feature, corrections))
+    }
+}
+
+#[cfg(feature = "parser")]
+fn parse_document(input: &str, env: &GherkinEnv) -> Result<GherkinDocument, ParseError> {
+    if env.mode() == ParseMode::Strict {
+        strict::check(input, env)?;
+    }
+
+    parser::gherkin_parser::document(input, env).map_err(ParseError::from_peg)
+}
+
+#[cfg(feature = "parser")]
+fn parse_markdown(input: &str, env: &GherkinEnv) -> Result<GherkinDocument, ParseError> {
+    let translation = markdown::Translation::new(input, &env.keywords());
+    env.set_markdown(true);
+    env.set_untitled(translation.untitled);
+    parser::gherkin_parser::document(&translation.text, env).map_err(ParseError::from_peg)
+}
+
+#[cfg(feature = "parser")]
+fn parse_feature(input: &str, env: &GherkinEnv) -> Result<Feature, ParseError> {
+    feature_of(parse_document(input, env)?, input)
+}
+
+#[cfg(feature = "parser")]
+fn parse_markdown_feature(input: &str, env: &GherkinEnv) -> Result<Feature, ParseError> {
+    feature_of(parse_markdown(input, env)?, input)
+}
+
+/// Takes the [`Feature`] of the `document` parsed from the `input`, failing
+/// at its end if there is none.
+#[cfg(feature = "parser")]
+fn feature_of(document: GherkinDocument, input: &str) -> Result<Feature, ParseError> {
+    document.feature.ok_or_else(|| ParseError {
+        position: LineCol {
+            line: input.lines().count() + 1,
+            col: 1,
+        },
+        expected: vec!["feature"],
+        error: None,
+    })
+}
+
+/// Reads the file at the `path` and parses it with the `parse` function, or
+/// with the `parse_markdown` one if it ends with `.md`.
+#[cfg(feature = "parser")]
+fn parse_path_with<T>(
+    path: &Path,
+    env: &GherkinEnv,
+    parse: fn(&str, &GherkinEnv) -> Result<T, ParseError>,
+    parse_markdown: fn(&str, &GherkinEnv) -> Result<T, ParseError>,
+) -> Result<T, ParseFileError> {
+    let s = std::fs::read_to_string(path).map_err(|e| ParseFileError::Reading {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+
+    let parse = if path.to_string_lossy().ends_with(".md") {
+        parse_markdown
+    } else {
+        parse
+    };
+    parse_with(&s, Some(path), env, parse).map_err(|e| ParseFileError::Parsing {
+        path: path.to_path_buf(),
+        error: e.error.as_deref().cloned(),
+        source: e,
+    })
+}
+
+/// Parses the `input` read from the `path`, if known, with the `parse`
+/// function in the language chosen by the [`LanguagePolicy`] of the `env`,
+/// making sure the `input` ends with a newline and attaching the
+/// [`EnvError`] the parsing failed with, if any.
+#[cfg(feature = "parser")]
+fn parse_with<T>(
+    input: &str,
+    path: Option<&Path>,
+    env: &GherkinEnv,
+    parse: impl FnOnce(&str, &GherkinEnv) -> Result<T, ParseError>,
+) -> Result<T, ParseError> {
+    use std::borrow::Cow;
+
+    let input: Cow<'_, str> = match input.ends_with('\n') {
+        true => Cow::Borrowed(input),
+        // Add a new line at the end, because our parser is bad and we should feel bad.
+        false => Cow::Owned(format!("{}\n", input)),
+    };
+    env.apply_language_policy(path, &input);
+    env.start_ids(&input);
+    parse(&input, env).map_err(|mut e| {
+        e.error = env
+            .fatal_error
+            .borrow_mut()
+            .take()
+            .or_else(|| env.last_error.borrow_mut().take())
+            .map(Box::new);
+        e
+    })
+}
+
+impl Feature {
+    /// Returns the names of the tags of the feature, without the leading `@`.
+    pub fn tag_names(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(|t| t.name.as_str())
+    }
+
+    /// Returns the background, scenarios and rules of the feature in the order they appear in the
+    /// .feature file, like the `children` of upstream.
+    ///
+    /// As the grammar allows no background after a scenario and no scenario of the feature after
+    /// a rule, this is the order of the fields.
+    pub fn children(&self) -> impl Iterator<Item = FeatureChild<'_>> {
+        self.background
+            .iter()
+            .map(FeatureChild::Background)
+            .chain(self.scenarios.iter().map(FeatureChild::Scenario))
+            .chain(self.rules.iter().map(FeatureChild::Rule))
+    }
+
+    /// Returns every scenario of the feature, including the ones of its rules, in the order they
+    /// appear in the .feature file, along with what they inherit from the feature and their rule.
+    pub fn effective_scenarios(&self) -> impl Iterator<Item = EffectiveScenario<'_>> {
+        let scenarios = self
+            .scenarios
+            .iter()
+            .map(move |s| self.effective_scenario(None, s));
+        let rules = self.rules.iter().flat_map(move |r| {
+            r.scenarios
+                .iter()
+                .map(move |s| self.effective_scenario(Some(r), s))
+        });
+        scenarios.chain(rules)
+    }
+
+    fn effective_scenario<'a>(
+        &'a self,
+        rule: Option<&'a Rule>,
+        scenario: &'a Scenario,
+    ) -> EffectiveScenario<'a> {
+        let tags = self
+            .tags
+            .iter()
+            .chain(rule.into_iter().flat_map(|r| &r.tags))
+            .chain(&scenario.tags)
+            .collect();
+        let background_steps = self
+            .background
+            .iter()
+            .chain(rule.and_then(|r| r.background.as_ref()))
+            .flat_map(|b| &b.steps)
+            .collect();
+
+        EffectiveScenario {
+            scenario,
+            rule,
+            tags,
+            background_steps,
+        }
+    }
+}
+
+/// A scenario of a [`Feature`] along with what it inherits from the feature and its rule, as
+/// returned by [`Feature::effective_scenarios()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EffectiveScenario<'a> {
+    /// The scenario.
+    pub scenario: &'a Scenario,
+    /// The rule of the scenario, if any.
+    pub rule: Option<&'a Rule>,
+    /// The tags of the feature, followed by the ones of the rule and of the scenario.
+    ///
+    /// Like upstream's pickles, a tag of both the scenario and its feature or rule is here twice.
+    pub tags: Vec<&'a Tag>,
+    /// The steps of the background of the feature, followed by the ones of the background of the
+    /// rule.
+    pub background_steps: Vec<&'a Step>,
+}
+
+impl<'a> EffectiveScenario<'a> {
+    /// Returns the names of the effective tags of the scenario, without the leading `@`.
+    pub fn tag_names(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.tags.iter().map(|t| t.name.as_str())
+    }
+
+    /// Returns the background steps of the scenario, followed by its own steps.
+    pub fn steps(&self) -> impl Iterator<Item = &'a Step> + '_ {
+        self.background_steps
+            .iter()
+            .copied()
+            .chain(&self.scenario.steps)
+    }
+}
+
+/// A child of a [`Feature`], as returned by [`Feature::children()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureChild<'a> {
+    Background(&'a Background),
+    Scenario(&'a Scenario),
+    Rule(&'a Rule),
 }
 
 impl PartialOrd for Feature {
@@ -260,6 +678,89 @@ impl Ord for Feature {
     }
 }
 
+/// A parsed .feature file
+#[cfg_attr(feature = "parser", derive(TypedBuilder))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "juniper", derive(juniper::GraphQLObject))]
+#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+pub struct GherkinDocument {
+    /// The path supplied for the parsed document, if known.
+    #[cfg_attr(feature = "parser", builder(default))]
+    #[cfg_attr(feature = "juniper", graphql(ignore))]
+    pub uri: Option<PathBuf>,
+    /// The feature of the document, if any, as a document may consist of comments only.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub feature: Option<Feature>,
+    /// The comments found anywhere in the document, in order.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub comments: Vec<Comment>,
+    /// The code of the language the document was parsed in, like `en`.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub language: String,
+    /// The `# language:` directive of the document, if found.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub language_directive: Option<LanguageDirective>,
+}
+
+impl GherkinDocument {
+    /// Returns the nodes of the feature of the document at the `location`, as
+    /// [`Feature::node_at()`] does.
+    pub fn node_at(&self, location: impl Into<Location>) -> Vec<NodeRef<'_>> {
+        self.feature
+            .as_ref()
+            .map_or_else(Vec::new, |f| f.node_at(location))
+    }
+}
+
+#[cfg(feature = "parser")]
+impl GherkinDocument {
+    /// Parses the .feature file at the `path`, or the Markdown with Gherkin one if it ends with
+    /// `.md`.
+    pub fn parse_path<P: AsRef<Path>>(
+        path: P,
+        env: GherkinEnv,
+    ) -> Result<GherkinDocument, ParseFileError> {
+        let path = path.as_ref();
+        let mut document = parse_path_with(path, &env, parse_document, parse_markdown)?;
+        document.uri = Some(path.to_path_buf());
+        if let Some(feature) = &mut document.feature {
+            feature.path = Some(path.to_path_buf());
+        }
+        Ok(document)
+    }
+
+    /// Parses the `input`, which may have no feature at all, like an empty .feature file.
+    pub fn parse<S: AsRef<str>>(input: S, env: GherkinEnv) -> Result<GherkinDocument, ParseError> {
+        parse_with(input.as_ref(), None, &env, parse_document)
+    }
+
+    /// Parses the `input` as Markdown with Gherkin, like
+    /// [`Feature::parse_markdown()`] does.
+    pub fn parse_markdown<S: AsRef<str>>(
+        input: S,
+        env: GherkinEnv,
+    ) -> Result<GherkinDocument, ParseError> {
+        parse_with(input.as_ref(), None, &env, parse_markdown)
+    }
+}
+
+/// A `# language:` directive
+#[cfg_attr(feature = "parser", derive(TypedBuilder))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "juniper", derive(juniper::GraphQLObject))]
+#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+pub struct LanguageDirective {
+    /// The code of the language set by the directive, like `fr`.
+    pub language: String,
+    /// The `(start, end)` offset the directive was found in the .feature file, from its `#` to
+    /// the end of the language code.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub span: Span,
+    /// The `(line, col)` position the `#` of the directive was found in the .feature file.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub position: LineCol,
+}
+
 /// A rule, as introduced in Gherkin 6.
 #[cfg_attr(feature = "parser", derive(TypedBuilder))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -272,7 +773,7 @@ pub struct Rule {
     pub name: String,
     /// The description of the rule, if found.
     #[cfg_attr(feature = "parser", builder(default))]
-    pub description: Option<String>,
+    pub description: Option<Description>,
     /// The background of the rule, if found.
     #[cfg_attr(feature = "parser", builder(default))]
     pub background: Option<Background>,
@@ -280,8 +781,16 @@ pub struct Rule {
     pub scenarios: Vec<Scenario>,
     /// The tags for the rule directive if provided.
     #[cfg_attr(feature = "parser", builder(default))]
-    pub tags: Vec<String>,
-    /// The `(start, end)` offset the rule directive was found in the .feature file.
+    pub tags: Vec<Tag>,
+    /// The id of the rule, unique within the parsed .feature file.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub id: String,
+    /// The `(start, end)` offset the header line of the rule was found in the .feature file, from
+    /// its keyword to the end of its name, without the trailing whitespace.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub header_span: Span,
+    /// The `(start, end)` offset the rule was found in the .feature file, from its keyword to the
+    /// end of its last line, including its description, background and scenarios, but not its tags.
     #[cfg_attr(feature = "parser", builder(default))]
     pub span: Span,
     /// The `(line, col)` position the rule directive was found in the .feature file.
@@ -289,6 +798,29 @@ pub struct Rule {
     pub position: LineCol,
 }
 
+impl Rule {
+    /// Returns the names of the tags of the rule, without the leading `@`.
+    pub fn tag_names(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(|t| t.name.as_str())
+    }
+
+    /// Returns the background and scenarios of the rule in the order they appear in the .feature
+    /// file, like the `children` of upstream.
+    pub fn children(&self) -> impl Iterator<Item = RuleChild<'_>> {
+        self.background
+            .iter()
+            .map(RuleChild::Background)
+            .chain(self.scenarios.iter().map(RuleChild::Scenario))
+    }
+}
+
+/// A child of a [`Rule`], as returned by [`Rule::children()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleChild<'a> {
+    Background(&'a Background),
+    Scenario(&'a Scenario),
+}
+
 /// A scenario
 #[cfg_attr(feature = "parser", derive(TypedBuilder))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -297,11 +829,14 @@ pub struct Rule {
 pub struct Scenario {
     /// The raw keyword used in the original source.
     pub keyword: String,
+    /// The family of the keyword used in the original source.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub kind: ScenarioKind,
     /// The name of the scenario.
     pub name: String,
     /// The description of the scenario, if found.
     #[cfg_attr(feature = "parser", builder(default))]
-    pub description: Option<String>,
+    pub description: Option<Description>,
     /// The parsed steps from the scenario directive.
     pub steps: Vec<Step>,
     // The parsed examples from the scenario directive if found.
@@ -309,8 +844,16 @@ pub struct Scenario {
     pub examples: Vec<Examples>,
     /// The tags for the scenarios directive if provided.
     #[cfg_attr(feature = "parser", builder(default))]
-    pub tags: Vec<String>,
-    /// The `(start, end)` offset the scenario directive was found in the .feature file.
+    pub tags: Vec<Tag>,
+    /// The id of the scenario, unique within the parsed .feature file.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub id: String,
+    /// The `(start, end)` offset the header line of the scenario was found in the .feature file,
+    /// from its keyword to the end of its name, without the trailing whitespace.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub header_span: Span,
+    /// The `(start, end)` offset the scenario was found in the .feature file, from its keyword to
+    /// the end of its last line, including its description, steps and examples, but not its tags.
     #[cfg_attr(feature = "parser", builder(default))]
     pub span: Span,
     /// The `(line, col)` position the scenario directive was found in the .feature file.
@@ -318,6 +861,50 @@ pub struct Scenario {
     pub position: LineCol,
 }
 
+impl Scenario {
+    /// Parses a single `Scenario` or `Scenario Outline` along with its
+    /// steps and examples from the `input`, in the language of the `env` or
+    /// the one set by a `# language:` directive.
+    #[cfg(feature = "parser")]
+    pub fn parse<S: AsRef<str>>(input: S, env: GherkinEnv) -> Result<Scenario, ParseError> {
+        parse_with(input.as_ref(), None, &env, |input, env| {
+            parser::gherkin_parser::scenario_fragment(input, env).map_err(ParseError::from_peg)
+        })
+    }
+
+    /// Returns the names of the tags of the scenario, without the leading `@`.
+    pub fn tag_names(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(|t| t.name.as_str())
+    }
+
+    /// Checks whether the scenario is a template for the rows of its examples, either by being a
+    /// `Scenario Outline`, by having examples, or by having `<placeholders>` in its steps.
+    pub fn is_templated(&self) -> bool {
+        self.kind == ScenarioKind::ScenarioOutline
+            || !self.examples.is_empty()
+            || self
+                .steps
+                .iter()
+                .any(|step| !step.placeholders().is_empty())
+    }
+}
+
+/// The family of a scenario keyword
+///
+/// `Example` is a `Scenario` keyword, as the language definitions list both in the same family
+/// without telling which one is which in languages other than English. The keyword as written is
+/// kept in [`Scenario::keyword`].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "juniper", derive(juniper::GraphQLEnum))]
+#[derive(Debug, Clone, Copy, PartialEq, Hash, Eq, Default)]
+pub enum ScenarioKind {
+    /// A `Scenario` or `Example` keyword.
+    #[default]
+    Scenario,
+    /// A `Scenario Outline` or `Scenario Template` keyword.
+    ScenarioOutline,
+}
+
 /// A scenario step
 #[cfg_attr(feature = "parser", derive(TypedBuilder))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -328,15 +915,27 @@ pub struct Step {
     pub keyword: String,
     /// The step type for the step after parsed in context.
     pub ty: StepType,
+    /// The type of the keyword used in the original source, before resolving `And`, `But` and `*`
+    /// in context.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub keyword_type: StepKeywordType,
     /// The value of the step after the type.
     pub value: String,
     /// A docstring, if provided.
     #[cfg_attr(feature = "parser", builder(default))]
-    pub docstring: Option<String>,
+    pub docstring: Option<DocString>,
     /// A data table, if provided.
     #[cfg_attr(feature = "parser", builder(default))]
     pub table: Option<Table>,
-    /// The `(start, end)` offset the step directive was found in the .feature file.
+    /// The id of the step, unique within the parsed .feature file.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub id: String,
+    /// The `(start, end)` offset the header line of the step was found in the .feature file, from
+    /// its keyword to the end of its text, without the trailing whitespace.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub header_span: Span,
+    /// The `(start, end)` offset the step was found in the .feature file, from its keyword to the
+    /// end of its last line, including its docstring or table.
     #[cfg_attr(feature = "parser", builder(default))]
     pub span: Span,
     /// The `(line, col)` position the step directive was found in the .feature file.
@@ -345,8 +944,21 @@ pub struct Step {
 }
 
 impl Step {
+    /// Parses a single step from the `input`, in the language of the `env` or
+    /// the one set by a `# language:` directive.
+    ///
+    /// An `And`, `But` or `*` step is considered a [`StepType::Given`] one,
+    /// as there is no preceding step to take the type of.
+    #[cfg(feature = "parser")]
+    pub fn parse<S: AsRef<str>>(input: S, env: GherkinEnv) -> Result<Step, ParseError> {
+        parse_with(input.as_ref(), None, &env, |input, env| {
+            parser::gherkin_parser::step_fragment(input, env).map_err(ParseError::from_peg)
+        })
+    }
+
+    /// Returns the content of the docstring, if provided.
     pub fn docstring(&self) -> Option<&String> {
-        self.docstring.as_ref()
+        self.docstring.as_ref().map(|d| &d.content)
     }
 
     pub fn table(&self) -> Option<&Table> {
@@ -370,6 +982,27 @@ pub enum StepType {
     Then,
 }
 
+/// The type of a step keyword as written, like upstream's
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "juniper", derive(juniper::GraphQLEnum))]
+#[derive(Debug, Clone, Copy, PartialEq, Hash, Eq, Default)]
+pub enum StepKeywordType {
+    /// A `Given`-like keyword, named `Context` by upstream, which can't be the name of a variant
+    /// deriving `juniper::GraphQLEnum`.
+    #[cfg_attr(feature = "serde", serde(rename = "Context"))]
+    #[cfg_attr(feature = "juniper", graphql(name = "CONTEXT"))]
+    Precondition,
+    /// A `When`-like keyword.
+    Action,
+    /// A `Then`-like keyword.
+    Outcome,
+    /// An `And`-like or `But`-like keyword.
+    Conjunction,
+    /// A keyword of several types, like `*`.
+    #[default]
+    Unknown,
+}
+
 /// A data table
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "parser", derive(TypedBuilder))]
@@ -377,8 +1010,9 @@ pub enum StepType {
 #[derive(Debug, Clone, PartialEq, Hash, Eq)]
 pub struct Table {
     /// The rows of the data table. Each row is always the same length as the first row.
-    pub rows: Vec<Vec<String>>,
-    /// The `(start, end)` offset the table directive was found in the .feature file.
+    pub rows: Vec<TableRow>,
+    /// The `(start, end)` offset the table was found in the .feature file, from the first `|` of
+    /// its first row to the last `|` of its last one.
     #[cfg_attr(feature = "parser", builder(default))]
     pub span: Span,
     /// The `(line, col)` position the table directive was found in the .feature file.
@@ -387,8 +1021,142 @@ pub struct Table {
 }
 
 impl Table {
+    /// Parses a data table from the `input`.
+    #[cfg(feature = "parser")]
+    pub fn parse<S: AsRef<str>>(input: S, env: GherkinEnv) -> Result<Table, ParseError> {
+        parse_with(input.as_ref(), None, &env, |input, env| {
+            parser::gherkin_parser::table_fragment(input, env).map_err(ParseError::from_peg)
+        })
+    }
+
     pub fn row_width(&self) -> usize {
-        self.rows.first().map(Vec::len).unwrap_or_default()
+        self.rows.first().map(|r| r.cells.len()).unwrap_or_default()
+    }
+
+    /// Returns the values of the cells of every row.
+    pub fn values(&self) -> Vec<Vec<&str>> {
+        self.rows.iter().map(TableRow::values).collect()
+    }
+
+    /// Returns the first row of the table, if any.
+    pub fn header(&self) -> Option<&TableRow> {
+        self.rows.first()
+    }
+
+    /// Returns the rows of the table following the first one.
+    pub fn body(&self) -> &[TableRow] {
+        self.rows.get(1..).unwrap_or_default()
+    }
+}
+
+/// A row of a data table
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "parser", derive(TypedBuilder))]
+#[cfg_attr(feature = "juniper", derive(juniper::GraphQLObject))]
+#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+pub struct TableRow {
+    /// The cells of the row.
+    pub cells: Vec<TableCell>,
+    /// The id of the row, unique within the parsed .feature file.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub id: String,
+    /// The `(start, end)` offset the row was found in the .feature file, from its first `|` to
+    /// its last one.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub span: Span,
+    /// The `(line, col)` position the first `|` of the row was found in the .feature file.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub position: LineCol,
+}
+
+impl TableRow {
+    /// Returns the values of the cells of the row.
+    pub fn values(&self) -> Vec<&str> {
+        self.cells.iter().map(|c| c.value.as_str()).collect()
+    }
+}
+
+/// A cell of a data table
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "parser", derive(TypedBuilder))]
+#[cfg_attr(feature = "juniper", derive(juniper::GraphQLObject))]
+#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+pub struct TableCell {
+    /// The value of the cell, trimmed and with escape sequences resolved.
+    pub value: String,
+    /// The `(start, end)` offset the value of the cell was found in the .feature file.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub span: Span,
+    /// The `(line, col)` position the value of the cell was found in the .feature file.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub position: LineCol,
+}
+
+impl AsRef<str> for TableCell {
+    fn as_ref(&self) -> &str {
+        &self.value
+    }
+}
+
+impl PartialEq<str> for TableCell {
+    fn eq(&self, other: &str) -> bool {
+        self.value == other
+    }
+}
+
+impl PartialEq<&str> for TableCell {
+    fn eq(&self, other: &&str) -> bool {
+        self.value == *other
+    }
+}
+
+impl Display for TableCell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+/// A tag
+#[cfg_attr(feature = "parser", derive(TypedBuilder))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "juniper", derive(juniper::GraphQLObject))]
+#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+pub struct Tag {
+    /// The name of the tag, without the leading `@`.
+    pub name: String,
+    /// The id of the tag, unique within the parsed .feature file and the same
+    /// for every parse of it.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub id: String,
+    /// The `(start, end)` offset the tag was found in the .feature file, including the `@`.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub span: Span,
+    /// The `(line, col)` position the tag was found in the .feature file.
+    #[cfg_attr(feature = "parser", builder(default))]
+    pub position: LineCol,
+}
+
+impl AsRef<str> for Tag {
+    fn as_ref(&self) -> &str {
+        &self.name
+    }
+}
+
+impl PartialEq<str> for Tag {
+    fn eq(&self, other: &str) -> bool {
+        self.name == other
+    }
+}
+
+impl PartialEq<&str> for Tag {
+    fn eq(&self, other: &&str) -> bool {
+        self.name == *other
+    }
+}
+
+impl Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "@{}", self.name)
     }
 }
 
@@ -396,7 +1164,49 @@ impl Table {
 #[error("Error at {}:{}: {expected:?}", .position.line, .position.col)]
 pub struct ParseError {
     position: LineCol,
-    expected: HashSet<&'static str>,
+    expected: Vec<&'static str>,
+    #[source]
+    error: Option<Box<ParseErrorCause>>,
+}
+
+#[cfg(feature = "parser")]
+type ParseErrorCause = EnvError;
+#[cfg(not(feature = "parser"))]
+type ParseErrorCause = std::convert::Infallible;
+
+impl ParseError {
+    #[cfg(feature = "parser")]
+    fn from_peg(e: peg::error::ParseError<peg::str::LineCol>) -> Self {
+        ParseError {
+            position: LineCol {
+                line: e.location.line,
+                col: e.location.column,
+            },
+            expected: {
+                let mut expected = e.expected.tokens().collect::<Vec<_>>();
+                expected.sort_unstable();
+                expected
+            },
+            error: None,
+        }
+    }
+
+    /// The `(line, col)` position the parsing failed at.
+    pub fn position(&self) -> LineCol {
+        self.position
+    }
+
+    /// The tokens expected at the [`position()`](ParseError::position).
+    pub fn expected(&self) -> &[&'static str] {
+        &self.expected
+    }
+
+    /// The error of the [`GherkinEnv`] the parsing failed with, like an
+    /// [`EnvError::InconsistentCellCount`], if any.
+    #[cfg(feature = "parser")]
+    pub fn error(&self) -> Option<&EnvError> {
+        self.error.as_deref()
+    }
 }
 
 #[cfg(feature = "parser")]
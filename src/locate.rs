@@ -0,0 +1,352 @@
+// Copyright (c) 2026  Brendan Molloy <brendan@bbqsrc.net>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Locating the nodes of a [`Feature`] at an offset or a position.
+
+use crate::{
+    Background, Description, DocString, Examples, Feature, LineCol, Rule, Scenario, Span, Step,
+    Table, TableCell, TableRow, Tag,
+};
+
+/// Location in a .feature file to find the nodes at via [`Feature::node_at()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    /// A byte offset, like the ones of [`Span`]s.
+    Offset(usize),
+    /// A `(line, col)` position, like the ones of the nodes.
+    LineCol(LineCol),
+}
+
+impl From<usize> for Location {
+    fn from(offset: usize) -> Self {
+        Location::Offset(offset)
+    }
+}
+
+impl From<LineCol> for Location {
+    fn from(position: LineCol) -> Self {
+        Location::LineCol(position)
+    }
+}
+
+/// A node of a [`Feature`], as returned by [`Feature::node_at()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeRef<'a> {
+    Feature(&'a Feature),
+    Background(&'a Background),
+    Rule(&'a Rule),
+    Scenario(&'a Scenario),
+    Examples(&'a Examples),
+    Step(&'a Step),
+    DocString(&'a DocString),
+    Table(&'a Table),
+    TableRow(&'a TableRow),
+    TableCell(&'a TableCell),
+    Tag(&'a Tag),
+    Description(&'a Description),
+}
+
+impl<'a> NodeRef<'a> {
+    /// Returns the `(start, end)` offset the node was found in the .feature file.
+    pub fn span(self) -> Span {
+        match self {
+            NodeRef::Feature(n) => n.span,
+            NodeRef::Background(n) => n.span,
+            NodeRef::Rule(n) => n.span,
+            NodeRef::Scenario(n) => n.span,
+            NodeRef::Examples(n) => n.span,
+            NodeRef::Step(n) => n.span,
+            NodeRef::DocString(n) => n.span,
+            NodeRef::Table(n) => n.span,
+            NodeRef::TableRow(n) => n.span,
+            NodeRef::TableCell(n) => n.span,
+            NodeRef::Tag(n) => n.span,
+            NodeRef::Description(n) => n.span,
+        }
+    }
+
+    /// Returns the `(line, col)` position the node was found in the .feature file.
+    pub fn position(self) -> LineCol {
+        match self {
+            NodeRef::Feature(n) => n.position,
+            NodeRef::Background(n) => n.position,
+            NodeRef::Rule(n) => n.position,
+            NodeRef::Scenario(n) => n.position,
+            NodeRef::Examples(n) => n.position,
+            NodeRef::Step(n) => n.position,
+            NodeRef::DocString(n) => n.position,
+            NodeRef::Table(n) => n.position,
+            NodeRef::TableRow(n) => n.position,
+            NodeRef::TableCell(n) => n.position,
+            NodeRef::Tag(n) => n.position,
+            NodeRef::Description(n) => n.position,
+        }
+    }
+
+    /// Returns the children of the node in the order they appear in the .feature file, tags
+    /// included.
+    fn children(self) -> Vec<NodeRef<'a>> {
+        let mut children = vec![];
+        let mut tags = |tags: &'a [Tag]| children.extend(tags.iter().map(NodeRef::Tag));
+        match self {
+            NodeRef::Feature(n) => {
+                tags(&n.tags);
+                children.extend(n.description.iter().map(NodeRef::Description));
+                children.extend(n.background.iter().map(NodeRef::Background));
+                children.extend(n.scenarios.iter().map(NodeRef::Scenario));
+                children.extend(n.rules.iter().map(NodeRef::Rule));
+            }
+            NodeRef::Rule(n) => {
+                tags(&n.tags);
+                children.extend(n.description.iter().map(NodeRef::Description));
+                children.extend(n.background.iter().map(NodeRef::Background));
+                children.extend(n.scenarios.iter().map(NodeRef::Scenario));
+            }
+            NodeRef::Background(n) => {
+                children.extend(n.description.iter().map(NodeRef::Description));
+                children.extend(n.steps.iter().map(NodeRef::Step));
+            }
+            NodeRef::Scenario(n) => {
+                tags(&n.tags);
+                children.extend(n.description.iter().map(NodeRef::Description));
+                children.extend(n.steps.iter().map(NodeRef::Step));
+                children.extend(n.examples.iter().map(NodeRef::Examples));
+            }
+            NodeRef::Examples(n) => {
+                tags(&n.tags);
+                children.extend(n.description.iter().map(NodeRef::Description));
+                children.extend(n.table.iter().map(NodeRef::Table));
+            }
+            NodeRef::Step(n) => {
+                children.extend(n.docstring.iter().map(NodeRef::DocString));
+                children.extend(n.table.iter().map(NodeRef::Table));
+            }
+            NodeRef::Table(n) => children.extend(n.rows.iter().map(NodeRef::TableRow)),
+            NodeRef::TableRow(n) => children.extend(n.cells.iter().map(NodeRef::TableCell)),
+            NodeRef::DocString(_)
+            | NodeRef::TableCell(_)
+            | NodeRef::Tag(_)
+            | NodeRef::Description(_) => {}
+        }
+        children
+    }
+
+    /// Returns the offsets spanned by the node along with its tags.
+    fn extent(self) -> Span {
+        let tags = self.children().into_iter().filter_map(|c| match c {
+            NodeRef::Tag(t) => Some(t.span.start),
+            _ => None,
+        });
+        let span = self.span();
+        Span {
+            start: tags.fold(span.start, usize::min),
+            end: span.end,
+        }
+    }
+
+    /// Returns the position the node starts at, its tags included, counting the whole line it
+    /// starts on as part of it, unless it may share the line with its siblings.
+    fn start(self) -> LineCol {
+        match self {
+            NodeRef::TableCell(_) | NodeRef::Tag(_) => self.position(),
+            _ => {
+                let line = self
+                    .children()
+                    .into_iter()
+                    .filter_map(|c| match c {
+                        NodeRef::Tag(t) => Some(t.position.line),
+                        _ => None,
+                    })
+                    .fold(self.position().line, usize::min);
+                LineCol { line, col: 0 }
+            }
+        }
+    }
+
+    /// Returns the last line of the node, its children included.
+    fn end_line(self) -> usize {
+        match self {
+            NodeRef::DocString(n) => {
+                let lines = if n.content.is_empty() {
+                    n.line_spans.len()
+                } else {
+                    n.content.split('\n').count()
+                };
+                // The closing delimiter follows the content.
+                n.position.line + lines + 1
+            }
+            // Comment lines are left out of the text, so a description
+            // interleaved with some is taken to end before its last lines.
+            NodeRef::Description(n) => n.position.line + n.text.lines().count().saturating_sub(1),
+            _ => self
+                .children()
+                .into_iter()
+                .map(NodeRef::end_line)
+                .fold(self.position().line, usize::max),
+        }
+    }
+
+    /// Returns the child containing the `location`, if any.
+    fn child_at(self, location: Location) -> Option<NodeRef<'a>> {
+        let children = self.children();
+        match location {
+            Location::Offset(offset) => children.into_iter().find(|c| {
+                let extent = c.extent();
+                extent.start <= offset && offset < extent.end
+            }),
+            Location::LineCol(position) => {
+                let key = |p: LineCol| (p.line, p.col);
+                let child = children
+                    .into_iter()
+                    .take_while(|c| key(c.start()) <= key(position))
+                    .last()?;
+                // The tags of the node end where it starts.
+                let own = LineCol {
+                    line: self.position().line,
+                    col: 0,
+                };
+                match child {
+                    NodeRef::Tag(_) if key(own) <= key(position) => None,
+                    _ if child.end_line() < position.line => None,
+                    _ => Some(child),
+                }
+            }
+        }
+    }
+}
+
+impl Feature {
+    /// Returns the nodes of the feature at the `location`, from the feature itself to the
+    /// innermost one, or nothing if the `location` is outside of the feature.
+    ///
+    /// At an offset, these are the nodes spanning it, along with their tags and children. At a
+    /// position, a node spans the whole lines from its first one, its tags included, to the last
+    /// one of its children, so the position of any line of a step, like the ones of its table,
+    /// gives the step, while the blank and comment lines after a node give its parent.
+    ///
+    /// ```
+    /// # use gherkin::{Feature, GherkinEnv, LineCol, NodeRef};
+    /// #
+    /// let input = "Feature: Foo\n  Scenario: Bar\n    Given a step\n      | a | b |\n";
+    /// let feature = Feature::parse(input, GherkinEnv::default()).unwrap();
+    ///
+    /// let path = feature.node_at(LineCol { line: 3, col: 1 });
+    /// assert_eq!(path.len(), 3);
+    /// assert_eq!(path[2], NodeRef::Step(&feature.scenarios[0].steps[0]));
+    ///
+    /// let path = feature.node_at(input.find('b').unwrap());
+    /// match path.last() {
+    ///     Some(NodeRef::TableCell(cell)) => assert_eq!(cell.value, "b"),
+    ///     node => panic!("{:?}", node),
+    /// }
+    /// ```
+    pub fn node_at(&self, location: impl Into<Location>) -> Vec<NodeRef<'_>> {
+        let location = location.into();
+        let feature = NodeRef::Feature(self);
+        let contains = match location {
+            Location::Offset(offset) => {
+                let extent = feature.extent();
+                extent.start <= offset && offset < extent.end
+            }
+            Location::LineCol(position) => {
+                feature.start().line <= position.line && position.line <= feature.end_line()
+            }
+        };
+        if !contains {
+            return vec![];
+        }
+
+        let mut path = vec![feature];
+        while let Some(child) = path.last().and_then(|n| n.child_at(location)) {
+            path.push(child);
+        }
+        path
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod test {
+    use super::*;
+    use crate::GherkinEnv;
+
+    const INPUT: &str = "@a\nFeature: Foo\n  Description\n\n  \
+                         @b @c\n  Scenario Outline: Bar\n    \
+                         Given a <x>\n      \"\"\"\n      text\n      \"\"\"\n\n    \
+                         Examples:\n      | x |\n      | 1 |\n\n  \
+                         Rule: Baz\n    Scenario: Qux\n      When another step\n";
+
+    fn kinds(path: Vec<NodeRef<'_>>) -> Vec<String> {
+        path.into_iter()
+            .map(|n| format!("{:?}", n).split('(').next().unwrap().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn at_offsets() {
+        let feature = Feature::parse(INPUT, GherkinEnv::default()).unwrap();
+        let at = |s: &str| kinds(feature.node_at(INPUT.find(s).unwrap()));
+
+        assert_eq!(at("@a"), ["Feature", "Tag"]);
+        assert_eq!(at("Foo"), ["Feature"]);
+        assert_eq!(at("Description"), ["Feature", "Description"]);
+        assert_eq!(at("@c"), ["Feature", "Scenario", "Tag"]);
+        assert_eq!(at("Bar"), ["Feature", "Scenario"]);
+        assert_eq!(at("<x>"), ["Feature", "Scenario", "Step"]);
+        assert_eq!(at("text"), ["Feature", "Scenario", "Step", "DocString"]);
+        assert_eq!(
+            at("1 |"),
+            [
+                "Feature",
+                "Scenario",
+                "Examples",
+                "Table",
+                "TableRow",
+                "TableCell"
+            ],
+        );
+        assert_eq!(at("Qux"), ["Feature", "Rule", "Scenario"]);
+        assert_eq!(at("another"), ["Feature", "Rule", "Scenario", "Step"]);
+        assert!(feature.node_at(INPUT.len()).is_empty());
+    }
+
+    #[test]
+    fn at_positions() {
+        let feature = Feature::parse(INPUT, GherkinEnv::default()).unwrap();
+        let at = |line, col| kinds(feature.node_at(LineCol { line, col }));
+
+        assert_eq!(at(1, 1), ["Feature", "Tag"]);
+        assert_eq!(at(2, 1), ["Feature"]);
+        assert_eq!(at(3, 1), ["Feature", "Description"]);
+        assert_eq!(at(5, 1), ["Feature", "Scenario"]);
+        assert_eq!(at(5, 6), ["Feature", "Scenario", "Tag"]);
+        assert_eq!(at(6, 3), ["Feature", "Scenario"]);
+        assert_eq!(at(7, 1), ["Feature", "Scenario", "Step"]);
+        assert_eq!(at(9, 1), ["Feature", "Scenario", "Step", "DocString"]);
+        assert_eq!(at(12, 5), ["Feature", "Scenario", "Examples"]);
+        assert_eq!(
+            at(14, 1),
+            ["Feature", "Scenario", "Examples", "Table", "TableRow"]
+        );
+        assert_eq!(
+            at(14, 9),
+            [
+                "Feature",
+                "Scenario",
+                "Examples",
+                "Table",
+                "TableRow",
+                "TableCell"
+            ],
+        );
+        assert_eq!(at(11, 1), ["Feature", "Scenario"]);
+        assert_eq!(at(15, 1), ["Feature"]);
+        assert_eq!(at(18, 7), ["Feature", "Rule", "Scenario", "Step"]);
+        assert!(at(19, 1).is_empty());
+        assert!(at(20, 1).is_empty());
+    }
+}
@@ -0,0 +1,350 @@
+// Copyright (c) 2026  Brendan Molloy <brendan@bbqsrc.net>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Markdown with Gherkin (`.feature.md` files).
+//!
+//! The Markdown is translated line by line into Gherkin the PEG grammar
+//! understands: Markdown markers (`#` of headings, `*` of bullets, backticks
+//! around tags) and all the other prose are replaced with spaces, so offsets
+//! in the translation are the same as in the Markdown source. The first line
+//! of a Markdown document without a `# Feature:` heading is kept as is, to be
+//! taken as the name of a `Feature` without a keyword, as upstream does.
+
+use crate::keywords::Keywords;
+
+/// Gherkin translated from Markdown.
+#[derive(Debug)]
+pub(crate) struct Translation {
+    /// The translated Gherkin source.
+    pub(crate) text: String,
+    /// The offset of the first line, if the Markdown has no `# Feature:`
+    /// heading, taken as the name of a `Feature` without a keyword.
+    pub(crate) untitled: Option<usize>,
+}
+
+impl Translation {
+    /// Translates the Markdown `input` using the `keywords`, unless a
+    /// `# language:` directive says otherwise.
+    pub(crate) fn new(input: &str, keywords: &Keywords<'static>) -> Self {
+        let mut translator = Translator {
+            keywords: keywords.clone(),
+            text: String::with_capacity(input.len()),
+            untitled: None,
+            feature: false,
+            after_step: false,
+            fence: None,
+        };
+        for line in input.split_inclusive('\n') {
+            translator.line(line);
+        }
+
+        Translation {
+            text: translator.text,
+            untitled: translator.untitled,
+        }
+    }
+}
+
+struct Translator {
+    keywords: Keywords<'static>,
+    text: String,
+    untitled: Option<usize>,
+    /// Whether the `Feature` line has been seen already.
+    feature: bool,
+    /// Whether the last meaningful line is a step, which may be followed by a
+    /// docstring.
+    after_step: bool,
+    /// The backticks of the fenced code block the current line is in, and
+    /// whether it is a docstring.
+    fence: Option<(String, bool)>,
+}
+
+impl Translator {
+    fn line(&mut self, line: &str) {
+        let content = line.trim_end_matches(['\r', '\n']);
+        let trimmed = content.trim_start();
+        let indent = content.len() - trimmed.len();
+
+        if let Some((fence, docstring)) = &self.fence {
+            let docstring = *docstring;
+            if trimmed.trim_end() == fence {
+                self.fence = None;
+                self.after_step = false;
+            }
+            return if docstring {
+                self.keep(line);
+            } else {
+                self.blank(line);
+            };
+        }
+
+        if trimmed.starts_with("```") {
+            let fence = trimmed.chars().take_while(|c| *c == '`').collect();
+            let docstring = self.after_step;
+            self.fence = Some((fence, docstring));
+            return if docstring {
+                self.keep(line);
+            } else {
+                self.blank(line);
+            };
+        }
+
+        if trimmed.is_empty() {
+            return self.keep(line);
+        }
+
+        if let Some(language) = language(trimmed) {
+            if let Some(keywords) = Keywords::get(language) {
+                self.keywords = keywords;
+            }
+            return self.keep(line);
+        }
+
+        if !self.feature && !has_tags(trimmed) {
+            self.feature = true;
+            self.after_step = false;
+            if let Some(len) = self.title(trimmed, &[self.keywords.feature]) {
+                return self.strip(line, indent, len);
+            }
+            self.untitled = Some(self.text.len() + indent);
+            return self.keep(line);
+        }
+
+        if is_table_row(content) {
+            return self.keep(line);
+        }
+        if let Some(len) = self.step(trimmed) {
+            self.after_step = true;
+            return self.strip(line, indent, len);
+        }
+        self.after_step = false;
+        if has_tags(trimmed) {
+            return self.tags(line);
+        }
+        let titles = [
+            self.keywords.background,
+            self.keywords.rule,
+            self.keywords.scenario_outline,
+            self.keywords.scenario,
+            self.keywords.examples,
+        ];
+        if let Some(len) = self.title(trimmed, &titles) {
+            return self.strip(line, indent, len);
+        }
+
+        self.blank(line);
+    }
+
+    /// Returns the length of the `#` heading marker, if the `trimmed` line is
+    /// a heading starting with any of the `keywords` followed by a colon.
+    fn title(&self, trimmed: &str, keywords: &[&[&str]]) -> Option<usize> {
+        let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+        if !(1..=6).contains(&hashes) {
+            return None;
+        }
+        let ws = trimmed[hashes..]
+            .chars()
+            .next()
+            .filter(|c| c.is_whitespace())?;
+        let len = hashes + ws.len_utf8();
+        let rest = &trimmed[len..];
+
+        keywords
+            .iter()
+            .flat_map(|k| k.iter())
+            .any(|k| rest.strip_prefix(k).is_some_and(|r| r.starts_with(':')))
+            .then_some(len)
+    }
+
+    /// Returns the length of the bullet marker, if the `trimmed` line is a
+    /// bullet starting with a step keyword other than `* `.
+    fn step(&self, trimmed: &str) -> Option<usize> {
+        let rest = trimmed.strip_prefix(['*', '+', '-'])?;
+        let rest = rest.trim_start();
+        let len = trimmed.len() - rest.len();
+
+        self.keywords
+            .steps()
+            .into_iter()
+            .filter(|k| *k != "* ")
+            .any(|k| rest.starts_with(k))
+            .then_some(len)
+    }
+
+    /// Keeps the tags wrapped in backticks and blanks the rest of the `line`.
+    fn tags(&mut self, line: &str) {
+        let start = self.text.len();
+        self.blank(line);
+        for (at, tag) in tags(line) {
+            self.text
+                .replace_range(start + at..start + at + tag.len(), tag);
+        }
+    }
+
+    /// Blanks the first `len` bytes of the `line` after its `indent`.
+    fn strip(&mut self, line: &str, indent: usize, len: usize) {
+        self.text.push_str(&line[..indent]);
+        self.blank(&line[indent..indent + len]);
+        self.text.push_str(&line[indent + len..]);
+    }
+
+    fn keep(&mut self, line: &str) {
+        self.text.push_str(line);
+    }
+
+    /// Replaces the `line` with the same number of bytes of spaces, keeping
+    /// its line break.
+    fn blank(&mut self, line: &str) {
+        let content = line.trim_end_matches(['\r', '\n']);
+        self.text.push_str(&" ".repeat(content.len()));
+        self.text.push_str(&line[content.len()..]);
+    }
+}
+
+fn has_tags(line: &str) -> bool {
+    tags(line).next().is_some()
+}
+
+/// Finds the `` `@tags` `` in the `line`, returning their offsets without
+/// the backticks.
+fn tags(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut rest = 0;
+    std::iter::from_fn(move || loop {
+        let start = rest + line[rest..].find("`@")? + 1;
+        let end = start + line[start..].find('`')?;
+        rest = end + 1;
+        if end > start + 1 {
+            return Some((start, &line[start..end]));
+        }
+    })
+}
+
+/// Returns the language of the `trimmed` line, if it's a `# language:`
+/// directive.
+fn language(trimmed: &str) -> Option<&str> {
+    let rest = trimmed.strip_prefix('#')?.trim_start();
+    let rest = rest.strip_prefix("language")?.trim_start();
+    Some(rest.strip_prefix(':')?.trim())
+}
+
+/// Checks whether the `content` line is a table row indented by 2 to 5
+/// whitespace characters, which is not a GFM table separator.
+fn is_table_row(content: &str) -> bool {
+    let indent = content.chars().take_while(|c| c.is_whitespace()).count();
+    if !(2..=5).contains(&indent) || !content.trim_start().starts_with('|') {
+        return false;
+    }
+    !content
+        .split('|')
+        .map(|cell| cell.trim_matches([' ', '\t']))
+        .any(|cell| {
+            let cell = cell.strip_prefix(':').unwrap_or(cell);
+            let cell = cell.strip_suffix(':').unwrap_or(cell);
+            !cell.is_empty() && cell.chars().all(|c| c == '-')
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::{Feature, GherkinDocument, GherkinEnv, LineCol};
+
+    #[test]
+    fn offsets_are_kept() {
+        let input = "# Feature: Foo\n\nSome prose.\n\n`@tag` and more\n## Scenario: Bar\n\n  - Given a table\n    | a | b |\n    | - | - |\n    | 1 | 2 |\n";
+        let feature = Feature::parse_markdown(input, GherkinEnv::default()).unwrap();
+
+        assert_eq!(feature.keyword, "Feature");
+        assert_eq!(feature.name, "Foo");
+        assert_eq!(feature.description, None);
+        let scenario = &feature.scenarios[0];
+        assert_eq!(scenario.tags, ["tag"]);
+        assert_eq!(scenario.position, LineCol { line: 6, col: 4 });
+        assert_eq!(&input[scenario.span.start..][..8], "Scenario");
+        let step = &scenario.steps[0];
+        assert_eq!(step.position, LineCol { line: 8, col: 5 });
+        assert_eq!(
+            &input[step.header_span.start..step.header_span.end],
+            "Given a table"
+        );
+        assert_eq!(
+            step.table.as_ref().unwrap().values(),
+            [["a", "b"], ["1", "2"]]
+        );
+    }
+
+    #[test]
+    fn feature_without_heading() {
+        let input = "Some title\n\nSome prose.\n\n# Scenario: Bar\n* Given a step\n";
+        let feature = Feature::parse_markdown(input, GherkinEnv::default()).unwrap();
+
+        assert_eq!(feature.keyword, "");
+        assert_eq!(feature.name, "Some title");
+        assert_eq!(feature.position, LineCol { line: 1, col: 1 });
+        assert_eq!(feature.span.start, 0);
+        let step = &feature.scenarios[0].steps[0];
+        assert_eq!(
+            &input[step.header_span.start..step.header_span.end],
+            "Given a step"
+        );
+    }
+
+    #[test]
+    fn positions_on_title_line() {
+        let input = "`@tag`
+  # Some *title*  
+
+# Scenario: Bar
+* Given a step
+";
+        let document = GherkinDocument::parse_markdown(input, GherkinEnv::default()).unwrap();
+        let feature = document.feature.unwrap();
+
+        assert!(document.comments.is_empty());
+        assert_eq!(feature.keyword, "");
+        assert_eq!(feature.name, "# Some *title*");
+        assert_eq!(feature.position, LineCol { line: 2, col: 3 });
+        assert_eq!(
+            &input[feature.header_span.start..feature.header_span.end],
+            "# Some *title*"
+        );
+        assert_eq!(&input[feature.tags[0].span.start..][..4], "@tag");
+        assert_eq!(feature.tags[0].position, LineCol { line: 1, col: 2 });
+        assert_eq!(feature.scenarios[0].position, LineCol { line: 4, col: 3 });
+        assert_eq!(
+            &input[feature.span.end - 12..feature.span.end],
+            "Given a step"
+        );
+    }
+
+    #[test]
+    fn code_blocks() {
+        let input = "# Feature: Foo\n\n```\n# Scenario: Not a scenario\n```\n\n## Scenario: Bar\n* Given a docstring\n  ````\n  ```\n  ````\n";
+        let feature = Feature::parse_markdown(input, GherkinEnv::default()).unwrap();
+
+        assert_eq!(feature.scenarios.len(), 1);
+        assert_eq!(
+            feature.scenarios[0].steps[0].docstring(),
+            Some(&"```".to_owned()),
+        );
+    }
+
+    #[test]
+    fn fixture_good() {
+        let d = env!("CARGO_MANIFEST_DIR");
+        let files = fs::read_dir(format!("{}/tests/fixtures/data/good/", d)).unwrap();
+        for file in files {
+            let path = file.unwrap().path();
+            if path.to_string_lossy().ends_with(".feature.md") {
+                let feature = Feature::parse_path(&path, GherkinEnv::default());
+                assert!(feature.is_ok(), "{:?}: {:?}", path, feature);
+            }
+        }
+    }
+}
@@ -0,0 +1,397 @@
+// Copyright (c) 2026  Brendan Molloy <brendan@bbqsrc.net>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Placeholders of scenario outlines.
+
+use std::fmt;
+
+use crate::{DocString, Examples, Feature, Scenario, ScenarioKind, Span, Step, TableCell};
+
+/// A `<placeholder>` of a scenario outline, replaced by the values of the
+/// column of the same name of its [`Examples`](crate::Examples).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Placeholder<'a> {
+    /// The name of the placeholder, without the `<` and `>`.
+    pub name: &'a str,
+    /// The `(start, end)` offset the placeholder was found in the .feature
+    /// file, including the `<` and `>`.
+    pub span: Span,
+}
+
+/// Finds the placeholders of the `text` found at the `start` offset, mapping
+/// its offsets into the ones of the .feature file with the `raw` function.
+fn find<'a>(
+    text: &'a str,
+    start: usize,
+    raw: impl Fn(usize) -> usize,
+    found: &mut Vec<Placeholder<'a>>,
+) {
+    let mut rest = 0;
+    while let Some(open) = text[rest..].find('<').map(|i| rest + i) {
+        let name = &text[open + 1..];
+        let Some(close) = name.find(['<', '>', '\n']) else {
+            break;
+        };
+        if name[close..].starts_with('>') && close > 0 {
+            found.push(Placeholder {
+                name: &name[..close],
+                span: Span {
+                    start: start + raw(open),
+                    end: start + raw(open + close + 2),
+                },
+            });
+            rest = open + close + 2;
+        } else {
+            rest = open + 1 + close;
+        }
+    }
+}
+
+/// Returns the number of bytes the first `len` ones of the `value` of a cell
+/// were written in, as the escape sequences of `\n`, `|` and `\` take two.
+fn raw_cell_len(value: &str, len: usize) -> usize {
+    len + value[..len].matches(['\n', '|', '\\']).count()
+}
+
+impl TableCell {
+    fn placeholders<'a>(&'a self, found: &mut Vec<Placeholder<'a>>) {
+        let start = self.span.start;
+        if raw_cell_len(&self.value, self.value.len()) == self.span.end - start {
+            find(&self.value, start, |i| raw_cell_len(&self.value, i), found);
+        } else {
+            // The backslash of an unknown escape sequence is kept, so where
+            // the escaped characters are can't be told from the value.
+            find(&self.value, start, |i| i, found);
+        }
+    }
+}
+
+impl DocString {
+    fn placeholders<'a>(&'a self, found: &mut Vec<Placeholder<'a>>) {
+        let delimiter = self.delimiter.get(..3).unwrap_or_default();
+
+        if let Some(media_type) = &self.media_type {
+            // Built without the span of its media type, it's taken to follow
+            // the opening delimiter right away.
+            let start = self
+                .media_type_span
+                .map_or(self.span.start + self.delimiter.len(), |s| s.start);
+            find(media_type, start, |i| i, found);
+        }
+        for (line, span) in self.content.split('\n').zip(&self.line_spans) {
+            // Every escaped delimiter takes three more bytes in the source, so
+            // that many delimiters of the line are taken as escaped ones.
+            let escapes = (span.end - span.start).saturating_sub(line.len()) / 3;
+            let unescaped = line
+                .match_indices(delimiter)
+                .map(|(i, _)| i)
+                .take(escapes)
+                .collect::<Vec<_>>();
+            let raw = |i: usize| i + 3 * unescaped.iter().filter(|u| **u < i).count();
+            find(line, span.start, raw, found);
+        }
+        if self.line_spans.is_empty() {
+            // Built without the spans of its lines, so where they are isn't
+            // known.
+            find(&self.content, self.span.start, |i| i, found);
+        }
+    }
+}
+
+impl Step {
+    /// Returns the `<placeholder>`s of the text, docstring and table of the
+    /// step, in the order they appear in the .feature file.
+    ///
+    /// ```
+    /// # use gherkin::{GherkinEnv, Step};
+    /// #
+    /// let input = "Given a <fruit> in a <box>\n  | <count> |\n";
+    /// let step = Step::parse(input, GherkinEnv::default()).unwrap();
+    ///
+    /// let placeholders = step.placeholders();
+    /// let names = placeholders.iter().map(|p| p.name).collect::<Vec<_>>();
+    /// assert_eq!(names, ["fruit", "box", "count"]);
+    /// let span = placeholders[1].span;
+    /// assert_eq!(&input[span.start..span.end], "<box>");
+    /// ```
+    pub fn placeholders(&self) -> Vec<Placeholder<'_>> {
+        let mut found = vec![];
+        // The text is the end of the header line, without the trailing
+        // whitespace.
+        let start = self.header_span.end.saturating_sub(self.value.len());
+        find(&self.value, start, |i| i, &mut found);
+        if let Some(docstring) = &self.docstring {
+            docstring.placeholders(&mut found);
+        }
+        for row in self.table.iter().flat_map(|t| &t.rows) {
+            for cell in &row.cells {
+                cell.placeholders(&mut found);
+            }
+        }
+        found
+    }
+}
+
+impl Scenario {
+    /// Returns the `<placeholder>`s of the name and the steps of the scenario,
+    /// in the order they appear in the .feature file.
+    pub fn placeholders(&self) -> Vec<Placeholder<'_>> {
+        let mut found = vec![];
+        let start = self.header_span.end.saturating_sub(self.name.len());
+        find(&self.name, start, |i| i, &mut found);
+        for step in &self.steps {
+            found.extend(step.placeholders());
+        }
+        found
+    }
+}
+
+/// A mismatch between the placeholders of a scenario outline and the columns
+/// of its [`Examples`], as found by [`Scenario::outline_issues()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlineIssue<'a> {
+    /// A placeholder of a step, its docstring or table, not named by any
+    /// column of the `examples`.
+    UnknownPlaceholder {
+        placeholder: Placeholder<'a>,
+        examples: &'a Examples,
+    },
+    /// A column of the `examples` replacing no placeholder of the name or the
+    /// steps of the scenario.
+    UnusedColumn {
+        column: &'a TableCell,
+        examples: &'a Examples,
+    },
+    /// A column of the `examples` named like the `first` one, which replaces
+    /// the placeholders instead.
+    DuplicateColumn {
+        column: &'a TableCell,
+        first: &'a TableCell,
+    },
+    /// Examples without a table, or without rows following its header, so
+    /// running the scenario outline for none.
+    EmptyExamples(&'a Examples),
+    /// Examples of a scenario not declared with a `Scenario Outline` keyword.
+    ExamplesWithoutOutline(&'a Examples),
+}
+
+impl OutlineIssue<'_> {
+    /// Returns the `(start, end)` offset of the issue in the .feature file:
+    /// the one of the placeholder or column, or the header line of the
+    /// examples.
+    pub fn span(&self) -> Span {
+        match self {
+            OutlineIssue::UnknownPlaceholder { placeholder, .. } => placeholder.span,
+            OutlineIssue::UnusedColumn { column, .. }
+            | OutlineIssue::DuplicateColumn { column, .. } => column.span,
+            OutlineIssue::EmptyExamples(examples)
+            | OutlineIssue::ExamplesWithoutOutline(examples) => examples.header_span,
+        }
+    }
+}
+
+impl fmt::Display for OutlineIssue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutlineIssue::UnknownPlaceholder { placeholder, .. } => write!(
+                f,
+                "placeholder `<{}>` is not a column of the examples",
+                placeholder.name,
+            ),
+            OutlineIssue::UnusedColumn { column, .. } => {
+                write!(f, "column `{}` is used by no placeholder", column.value)
+            }
+            OutlineIssue::DuplicateColumn { column, first } => write!(
+                f,
+                "column `{}` is a duplicate of the one at ({}:{})",
+                column.value, first.position.line, first.position.col,
+            ),
+            OutlineIssue::EmptyExamples(_) => write!(f, "examples have no rows"),
+            OutlineIssue::ExamplesWithoutOutline(_) => {
+                write!(f, "examples of a scenario which isn't an outline")
+            }
+        }
+    }
+}
+
+impl Scenario {
+    /// Checks the placeholders of the scenario against the columns of its
+    /// examples, returning the issues found in the order they appear in the
+    /// .feature file.
+    ///
+    /// Placeholders of the name of the scenario aren't required to be
+    /// columns, as names often have `<` and `>` in them, yet they use the
+    /// columns they name.
+    ///
+    /// ```
+    /// # use gherkin::{GherkinEnv, OutlineIssue, Scenario};
+    /// #
+    /// let input = "Scenario Outline: Eating\n  \
+    ///              Given there are <start> cucumbers\n  \
+    ///              When I eat <eat> cucumbers\n\n  \
+    ///              Examples:\n    | start | left |\n    | 12    | 7    |\n";
+    /// let scenario = Scenario::parse(input, GherkinEnv::default()).unwrap();
+    ///
+    /// let issues = scenario.outline_issues();
+    /// assert_eq!(issues.len(), 2);
+    /// assert!(matches!(issues[0], OutlineIssue::UnknownPlaceholder { .. }));
+    /// assert_eq!(issues[0].to_string(), "placeholder `<eat>` is not a column of the examples");
+    /// let span = issues[1].span();
+    /// assert_eq!(&input[span.start..span.end], "left");
+    /// ```
+    pub fn outline_issues(&self) -> Vec<OutlineIssue<'_>> {
+        let mut issues = vec![];
+        let used = self.placeholders();
+        let step_placeholders = self
+            .steps
+            .iter()
+            .flat_map(Step::placeholders)
+            .collect::<Vec<_>>();
+
+        for examples in &self.examples {
+            if self.kind != ScenarioKind::ScenarioOutline {
+                issues.push(OutlineIssue::ExamplesWithoutOutline(examples));
+            }
+            if examples.body().is_empty() {
+                issues.push(OutlineIssue::EmptyExamples(examples));
+            }
+            let Some(header) = examples.header() else {
+                continue;
+            };
+
+            for placeholder in &step_placeholders {
+                if !header.cells.iter().any(|c| c.value == placeholder.name) {
+                    issues.push(OutlineIssue::UnknownPlaceholder {
+                        placeholder: *placeholder,
+                        examples,
+                    });
+                }
+            }
+            for (i, column) in header.cells.iter().enumerate() {
+                if let Some(first) = header.cells[..i].iter().find(|c| c.value == column.value) {
+                    issues.push(OutlineIssue::DuplicateColumn { column, first });
+                } else if !used.iter().any(|p| p.name == column.value) {
+                    issues.push(OutlineIssue::UnusedColumn { column, examples });
+                }
+            }
+        }
+
+        issues.sort_by_key(|i| i.span().start);
+        issues
+    }
+}
+
+impl Feature {
+    /// Returns the [`OutlineIssue`]s of every scenario of the feature and its
+    /// rules, in the order they appear in the .feature file.
+    pub fn outline_issues(&self) -> Vec<OutlineIssue<'_>> {
+        self.scenarios
+            .iter()
+            .chain(self.rules.iter().flat_map(|r| &r.scenarios))
+            .flat_map(Scenario::outline_issues)
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod test {
+    use super::*;
+    use crate::{GherkinEnv, ParseMode};
+
+    fn found(input: &str, env: GherkinEnv) -> Vec<String> {
+        let scenario = Scenario::parse(input, env).unwrap();
+        scenario
+            .placeholders()
+            .into_iter()
+            .map(|p| format!("{} {}", p.name, &input[p.span.start..p.span.end]))
+            .collect()
+    }
+
+    #[test]
+    fn spans() {
+        let input = "Scenario Outline: A <name>  \n  \
+                     Given a <a> and <b c>, not <> or < <d>\n    \
+                     \"\"\"<m>\n      <e>\n    \\\"\\\"\\\" <f>\n    \"\"\"\n  \
+                     When a table\n    | <g> | \\|<h> | \\n\\\\<i> |\n";
+        assert_eq!(
+            found(input, GherkinEnv::default()),
+            [
+                "name <name>",
+                "a <a>",
+                "b c <b c>",
+                "d <d>",
+                "m <m>",
+                "e <e>",
+                "f <f>",
+                "g <g>",
+                "h <h>",
+                "i <i>",
+            ],
+        );
+    }
+
+    #[test]
+    fn media_type_after_whitespace() {
+        let input = "Scenario Outline: A\n  Given a\n    \"\"\" \t<type>\n    \"\"\"\n";
+        assert_eq!(found(input, GherkinEnv::default()), ["type <type>"]);
+    }
+
+    #[test]
+    fn strict_escapes() {
+        let input = "Scenario Outline: A\n  Given a table\n    | \\a<b> |\n";
+        let env = GherkinEnv::default().with_mode(ParseMode::Strict);
+        assert_eq!(found(input, env), ["b <b>"]);
+    }
+
+    #[test]
+    fn outline_issues() {
+        let input = "Feature: Foo\n  \
+                     Scenario Outline: A <name>\n    \
+                     Given a <x>\n      | <y> |\n\n    \
+                     Examples:\n      | x | name | x | z |\n      | 1 | 2    | 3 | 4 |\n\n    \
+                     Examples:\n      | x | y |\n\n  \
+                     Rule: Bar\n    \
+                     Scenario: B\n      Given a step\n\n      \
+                     Examples:\n";
+        let feature = Feature::parse(input, GherkinEnv::default()).unwrap();
+
+        let issues = feature
+            .outline_issues()
+            .into_iter()
+            .map(|i| format!("{} {}", &input[i.span().start..i.span().end], i))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            issues,
+            [
+                "<y> placeholder `<y>` is not a column of the examples",
+                "x column `x` is a duplicate of the one at (7:9)",
+                "z column `z` is used by no placeholder",
+                "Examples: examples have no rows",
+                "Examples: examples of a scenario which isn't an outline",
+                "Examples: examples have no rows",
+            ],
+        );
+    }
+
+    #[test]
+    fn no_outline_issues() {
+        let input =
+            "Scenario Outline: A <x>\n  Given a <y>\n  Examples:\n    | x | y |\n    | 1 | 2 |\n";
+        let scenario = Scenario::parse(input, GherkinEnv::default()).unwrap();
+        assert!(scenario.outline_issues().is_empty());
+
+        let input = "Scenario Outline: A\n  Given a step\n    ```<type>\n    {}\n    ```\n  \
+                     Examples:\n    | type |\n    | json |\n";
+        let scenario = Scenario::parse(input, GherkinEnv::default()).unwrap();
+        assert!(scenario.outline_issues().is_empty());
+
+        let input = "Scenario: A\n  Given a <y>\n";
+        let scenario = Scenario::parse(input, GherkinEnv::default()).unwrap();
+        assert!(scenario.outline_issues().is_empty());
+    }
+}
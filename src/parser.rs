@@ -6,23 +6,61 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use std::{cell::RefCell, iter};
-
-use crate::{keywords::Keywords, tagexpr::TagOperation};
-use crate::{Background, Examples, Feature, LineCol, Rule, Scenario, Span, Step, StepType, Table};
+use std::{cell::RefCell, path::Path};
+
+use crate::{forgiving, keywords::Keywords, strict::StrictError, tagexpr::TagOperation};
+use crate::{
+    Background, Comment, Description, DocString, Examples, Feature, GherkinDocument, IdGenerator,
+    IncrementingIdGenerator, LanguageDirective, LanguagePolicy, LineCol, Rule, Scenario,
+    ScenarioKind, Span, Step, StepKeywordType, StepType, Table, TableCell, TableRow, Tag,
+};
+
+/// How closely the input must follow the upstream Gherkin grammar.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum ParseMode {
+    /// Accepts the full gamut of `.feature` files found in the wild, even the
+    /// ones the upstream Gherkin parser rejects.
+    #[default]
+    Lenient,
+
+    /// Rejects exactly what the upstream Gherkin parser rejects, reporting the
+    /// same errors as it does via [`EnvError::Strict`].
+    ///
+    /// Applies to whole features only, as upstream has no grammar for parsing
+    /// a `Step::parse()` or other fragment on its own.
+    Strict,
+
+    /// Matches keywords case-insensitively, tolerating a missing or extra
+    /// colon and stray whitespace around them. The keywords of the parsed
+    /// [`Feature`](crate::Feature) are spelled as in the language definition,
+    /// with the applied fixes reported by
+    /// [`Feature::parse_forgiving()`](crate::Feature::parse_forgiving).
+    ///
+    /// Note that a description line starting with a title keyword followed by
+    /// whitespace (like `Rule of thumb`) is taken as a title in this mode.
+    Forgiving,
+}
 
 #[derive(Debug)]
 pub struct GherkinEnv {
     keywords: RefCell<Keywords<'static>>,
+    language: RefCell<String>,
+    mode: ParseMode,
+    language_policy: LanguagePolicy,
     pub(crate) last_error: RefCell<Option<EnvError>>,
     pub(crate) fatal_error: RefCell<Option<EnvError>>,
     last_step: RefCell<Option<StepType>>,
-    last_keyword: RefCell<Option<String>>,
+    last_keyword: RefCell<Option<(String, usize)>>,
     line_offsets: RefCell<Vec<usize>>,
+    source: RefCell<String>,
+    comments: RefCell<Vec<Comment>>,
+    id_generator: RefCell<Box<dyn IdGenerator>>,
     was_escaped: RefCell<bool>,
+    markdown: RefCell<bool>,
+    untitled: RefCell<Option<usize>>,
 }
 
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum EnvError {
     #[error("Requested language '{0}' is not supported.")]
     UnsupportedLanguage(String),
@@ -32,6 +70,17 @@ pub enum EnvError {
 
     #[error("Inconsistent cell count")]
     InconsistentCellCount(Vec<Vec<String>>),
+
+    #[error("Input does not conform to the Gherkin grammar: {}", display_all(.0))]
+    Strict(Vec<StrictError>),
+}
+
+fn display_all(errors: &[StrictError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 impl GherkinEnv {
@@ -41,6 +90,7 @@ impl GherkinEnv {
 
         Ok(Self {
             keywords: RefCell::new(keywords),
+            language: RefCell::new(language.into()),
             ..Default::default()
         })
     }
@@ -52,10 +102,51 @@ impl GherkinEnv {
         })?;
 
         *self.keywords.borrow_mut() = keywords;
+        *self.language.borrow_mut() = language.into();
 
         Ok(())
     }
 
+    /// Sets how closely the input must follow the upstream Gherkin grammar.
+    pub fn with_mode(mut self, mode: ParseMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn mode(&self) -> ParseMode {
+        self.mode
+    }
+
+    /// Sets how the language of an input without a `# language:` directive
+    /// is chosen, instead of using the language of this environment.
+    pub fn with_language_policy(mut self, policy: LanguagePolicy) -> Self {
+        self.language_policy = policy;
+        self
+    }
+
+    /// Sets how the ids of the parsed nodes are generated, instead of
+    /// numbering them with an [`IncrementingIdGenerator`].
+    pub fn with_id_generator<G: IdGenerator + 'static>(self, generator: G) -> Self {
+        *self.id_generator.borrow_mut() = Box::new(generator);
+        self
+    }
+
+    /// Starts generating the ids of the nodes parsed from the `input`.
+    pub(crate) fn start_ids(&self, input: &str) {
+        self.id_generator.borrow_mut().start(input);
+    }
+
+    /// Switches to the language the [`LanguagePolicy`] chooses for the
+    /// `input` read from the `path`, if any.
+    pub(crate) fn apply_language_policy(&self, path: Option<&Path>, input: &str) {
+        if let Some(language) = self.language_policy.language_of(path, input) {
+            if let Some(keywords) = Keywords::get(language) {
+                *self.keywords.borrow_mut() = keywords;
+                *self.language.borrow_mut() = language.into();
+            }
+        }
+    }
+
     fn assert_no_error(&self) -> Result<(), &'static str> {
         if self.fatal_error.borrow().is_some() {
             return Err("fatal error");
@@ -64,7 +155,7 @@ impl GherkinEnv {
         Ok(())
     }
 
-    fn set_fatal_error(&self, error: EnvError) {
+    pub(crate) fn set_fatal_error(&self, error: EnvError) {
         if self.fatal_error.borrow().is_some() {
             return;
         }
@@ -76,30 +167,92 @@ impl GherkinEnv {
         *self.last_error.borrow_mut() = Some(error);
     }
 
-    fn keywords(&self) -> std::cell::Ref<'_, Keywords<'static>> {
+    /// Returns the code of the language the input is parsed in.
+    pub(crate) fn language(&self) -> String {
+        self.language.borrow().clone()
+    }
+
+    pub(crate) fn keywords(&self) -> std::cell::Ref<'_, Keywords<'static>> {
         self.keywords.borrow()
     }
 
-    fn set_keyword(&self, kw: String) {
-        *self.last_keyword.borrow_mut() = Some(kw);
+    fn set_keyword(&self, kw: String, len: usize) {
+        *self.last_keyword.borrow_mut() = Some((kw, len));
     }
 
     fn clear_keyword(&self) {
         *self.last_keyword.borrow_mut() = None;
     }
 
-    fn last_keyword(&self) -> std::cell::Ref<'_, Option<String>> {
+    fn last_keyword(&self) -> std::cell::Ref<'_, Option<(String, usize)>> {
         self.last_keyword.borrow()
     }
 
-    fn take_keyword(&self) -> String {
+    fn take_keyword(&self) -> (String, usize) {
         self.last_keyword.borrow_mut().take().unwrap()
     }
 
+    /// Finds the keyword of the `list` the `input` starts with, along with the
+    /// number of characters it spans in the `input`.
+    fn match_keyword<'a>(&self, list: &[&'a str], input: &str) -> Option<(&'a str, usize)> {
+        if self.mode == ParseMode::Forgiving {
+            // As whitespace is ignored, `scenario outline` matches `Scenario`
+            // too, so the longest of all the keywords wins.
+            let longest = |list: &[&'a str]| {
+                list.iter()
+                    .filter_map(|kw| Some((*kw, forgiving::match_keyword(input, kw)?)))
+                    .max_by_key(|(_, len)| *len)
+            };
+            let found = longest(list)?;
+            match longest(&self.keywords().all()) {
+                Some((_, len)) if len > found.1 => None,
+                _ => Some(found),
+            }
+        } else {
+            list.iter()
+                .find(|kw| input.starts_with(**kw))
+                .map(|kw| (*kw, kw.chars().count()))
+        }
+    }
+
     fn set_last_step(&self, ty: StepType) {
         *self.last_step.borrow_mut() = Some(ty);
     }
 
+    /// Resolves the type of the step with the `keyword`, taking the one of
+    /// the preceding step for `And`, `But` and ambiguous keywords like `*`.
+    fn step_type(&self, keyword: &str) -> (StepType, StepKeywordType) {
+        let keywords = self.keywords();
+        let types = [
+            (keywords.given, StepKeywordType::Precondition),
+            (keywords.when, StepKeywordType::Action),
+            (keywords.then, StepKeywordType::Outcome),
+            (keywords.and, StepKeywordType::Conjunction),
+            (keywords.but, StepKeywordType::Conjunction),
+        ]
+        .iter()
+        .filter(|(list, _)| list.contains(&keyword))
+        .map(|(_, ty)| *ty)
+        .collect::<Vec<_>>();
+
+        // Like upstream, a keyword listed more than once is ambiguous.
+        let keyword_type = match types.as_slice() {
+            [ty] => *ty,
+            _ => StepKeywordType::Unknown,
+        };
+        let ty = match keyword_type {
+            StepKeywordType::Precondition => StepType::Given,
+            StepKeywordType::Action => StepType::When,
+            StepKeywordType::Outcome => StepType::Then,
+            StepKeywordType::Conjunction | StepKeywordType::Unknown => {
+                self.last_step().unwrap_or(StepType::Given)
+            }
+        };
+        self.set_last_step(ty);
+
+        (ty, keyword_type)
+    }
+
     fn clear_last_step(&self) {
         *self.last_step.borrow_mut() = None;
     }
@@ -115,6 +268,248 @@ impl GherkinEnv {
         }
     }
 
+    /// Records the comment `text` found at `start`, unless it follows other
+    /// text on its line or was already recorded when backtracking.
+    fn add_comment(&self, text: &str, start: usize) {
+        let position = self.position(start);
+        let mut comments = self.comments.borrow_mut();
+        if position.col == 1 && !comments.iter().any(|c| c.span.start == start) {
+            comments.push(
+                Comment::builder()
+                    .text(text.into())
+                    .span(Span {
+                        start,
+                        end: start + text.len(),
+                    })
+                    .position(position)
+                    .build(),
+            );
+        }
+    }
+
+    fn take_comments(&self) -> Vec<Comment> {
+        let mut comments = self.comments.take();
+        comments.sort_by_key(|c| c.span.start);
+        comments
+    }
+
+    /// Builds a [`DocString`] opened with the `delimiter` at the start of the
+    /// `span` and having the `lines`, stripping them of the indentation of
+    /// the opening `delimiter` and unescaping the escaped delimiters, as
+    /// upstream does.
+    fn docstring(
+        &self,
+        delimiter: &str,
+        media_type: Option<&str>,
+        lines: &[(usize, &str)],
+        span: Span,
+    ) -> DocString {
+        let position = self.position(span.start);
+        let indent = position.col - 1;
+        let escaped = delimiter
+            .chars()
+            .take(3)
+            .flat_map(|c| ['\\', c])
+            .collect::<String>();
+        let (line_spans, content): (Vec<_>, Vec<_>) = lines
+            .iter()
+            .map(|(start, l)| {
+                let stripped = if l.chars().take_while(|c| c.is_whitespace()).count() < indent {
+                    l.trim_start()
+                } else {
+                    &l[l.char_indices().nth(indent).map_or(l.len(), |(i, _)| i)..]
+                };
+                let end = start + l.len();
+                let span = Span {
+                    start: end - stripped.len(),
+                    end,
+                };
+                (span, stripped.replace(&escaped, &delimiter[..3]))
+            })
+            .unzip();
+        let content = content.join("\n");
+        let media_type = media_type
+            .map(|m| (m.len() - m.trim_start().len(), m.trim()))
+            .filter(|(_, m)| !m.is_empty());
+        let media_type_span = media_type.map(|(leading, m)| {
+            let start = span.start + delimiter.len() + leading;
+            Span {
+                start,
+                end: start + m.len(),
+            }
+        });
+
+        DocString::builder()
+            .content(content)
+            .delimiter(delimiter.into())
+            .media_type(media_type.map(|(_, m)| m.into()))
+            .media_type_span(media_type_span)
+            .span(span)
+            .line_spans(line_spans)
+            .position(position)
+            .build()
+    }
+
+    /// Builds the [`Description`] of the `lines` spanning the `(start, end)`
+    /// offsets of their text, if any, keeping their indentation and the empty
+    /// lines between them, as upstream does.
+    fn description(&self, lines: &[(usize, usize)]) -> Option<Description> {
+        let (first, _) = *lines.first()?;
+        let (_, end) = *lines.last()?;
+        let start = self.line_offsets.borrow()[self.position(first).line - 1];
+        // The comment lines between the lines are collected on their own.
+        let text = self.source.borrow()[start..end]
+            .split('\n')
+            .map(|l| l.strip_suffix('\r').unwrap_or(l))
+            .filter(|l| !l.trim_start().starts_with('#'))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Some(
+            Description::builder()
+                .text(text)
+                .span(Span { start, end })
+                .position(self.position(start))
+                .build(),
+        )
+    }
+
+    /// Builds a [`TableCell`] of the `chars` starting at `start`, trimmed of
+    /// whitespace.
+    fn table_cell(&self, chars: &[(&str, usize, usize)], start: usize) -> TableCell {
+        let is_value = |(c, _, _): &(&str, usize, usize)| !c.chars().all(is_cell_padding);
+        let value = match (
+            chars.iter().position(is_value),
+            chars.iter().rposition(is_value),
+        ) {
+            (Some(first), Some(last)) => &chars[first..=last],
+            _ => &[],
+        };
+        let span = match (value.first(), value.last()) {
+            (Some((_, start, _)), Some((_, _, end))) => Span {
+                start: *start,
+                end: *end,
+            },
+            _ => {
+                let end = chars.last().map_or(start, |(_, _, end)| *end);
+                Span { start: end, end }
+            }
+        };
+
+        TableCell::builder()
+            .value(value.iter().map(|(c, _, _)| *c).collect())
+            .span(span)
+            .position(self.position(span.start))
+            .build()
+    }
+
+    /// Builds the [`GherkinDocument`] of the `feature` parsed along with the
+    /// `language_directive`, if any, assigning ids to its nodes.
+    fn document(
+        &self,
+        language_directive: Option<LanguageDirective>,
+        feature: Option<Feature>,
+    ) -> Result<GherkinDocument, &'static str> {
+        self.assert_no_error()?;
+
+        let mut feature = feature;
+        if let Some(feature) = &mut feature {
+            self.assign_feature_ids(feature);
+        }
+        Ok(GherkinDocument::builder()
+            .feature(feature)
+            .comments(self.take_comments())
+            .language(self.language())
+            .language_directive(language_directive)
+            .build())
+    }
+
+    fn next_id(&self) -> String {
+        self.id_generator.borrow_mut().next_id()
+    }
+
+    /// Assigns ids to the nodes of the `feature` once it's parsed, in the
+    /// order upstream does: every node after its children and tags.
+    fn assign_feature_ids(&self, feature: &mut Feature) {
+        if let Some(background) = &mut feature.background {
+            self.assign_background_ids(background);
+        }
+        for scenario in &mut feature.scenarios {
+            self.assign_scenario_ids(scenario);
+        }
+        for rule in &mut feature.rules {
+            self.assign_rule_ids(rule);
+        }
+        self.assign_tag_ids(&mut feature.tags);
+        feature.id = self.next_id();
+    }
+
+    fn assign_rule_ids(&self, rule: &mut Rule) {
+        if let Some(background) = &mut rule.background {
+            self.assign_background_ids(background);
+        }
+        for scenario in &mut rule.scenarios {
+            self.assign_scenario_ids(scenario);
+        }
+        self.assign_tag_ids(&mut rule.tags);
+        rule.id = self.next_id();
+    }
+
+    fn assign_background_ids(&self, background: &mut Background) {
+        for step in &mut background.steps {
+            self.assign_step_ids(step);
+        }
+        background.id = self.next_id();
+    }
+
+    fn assign_scenario_ids(&self, scenario: &mut Scenario) {
+        for step in &mut scenario.steps {
+            self.assign_step_ids(step);
+        }
+        for examples in &mut scenario.examples {
+            self.assign_examples_ids(examples);
+        }
+        self.assign_tag_ids(&mut scenario.tags);
+        scenario.id = self.next_id();
+    }
+
+    fn assign_examples_ids(&self, examples: &mut Examples) {
+        if let Some(table) = &mut examples.table {
+            self.assign_table_ids(table);
+        }
+        self.assign_tag_ids(&mut examples.tags);
+        examples.id = self.next_id();
+    }
+
+    fn assign_step_ids(&self, step: &mut Step) {
+        if let Some(table) = &mut step.table {
+            self.assign_table_ids(table);
+        }
+        step.id = self.next_id();
+    }
+
+    fn assign_table_ids(&self, table: &mut Table) {
+        for row in &mut table.rows {
+            row.id = self.next_id();
+        }
+    }
+
+    fn assign_tag_ids(&self, tags: &mut [Tag]) {
+        for tag in tags {
+            tag.id = self.next_id();
+        }
+    }
+
+    /// Returns the span of the header line of a node from `start` to `end`, without its trailing
+    /// whitespace.
+    fn header_span(&self, start: usize, end: usize) -> Span {
+        let source = self.source.borrow();
+        let end = source
+            .get(start..end)
+            .map_or(end, |s| start + s.trim_end().len());
+        Span { start, end }
+    }
+
     fn position(&self, offset: usize) -> LineCol {
         let line_offsets = self.line_offsets.borrow();
         let line = line_offsets
@@ -122,11 +517,33 @@ impl GherkinEnv {
             .position(|x| x > &offset)
             .unwrap_or(line_offsets.len());
 
-        let col = offset - line_offsets[line - 1] + 1;
+        // Upstream counts columns in characters.
+        let line_start = line_offsets[line - 1];
+        let col = self
+            .source
+            .borrow()
+            .get(line_start..offset)
+            .map_or(offset - line_start, |s| s.chars().count())
+            + 1;
 
         LineCol { line, col }
     }
 
+    fn set_source(&self, source: &str) {
+        *self.source.borrow_mut() = source.into();
+    }
+
+    /// Sets whether the input is Gherkin translated from Markdown.
+    pub(crate) fn set_markdown(&self, markdown: bool) {
+        *self.markdown.borrow_mut() = markdown;
+    }
+
+    /// Sets the offset of the name of a `Feature` without a keyword, as the
+    /// first line of Markdown without a `# Feature:` heading is.
+    pub(crate) fn set_untitled(&self, untitled: Option<usize>) {
+        *self.untitled.borrow_mut() = untitled;
+    }
+
     fn escaped(&self) -> bool {
         *self.was_escaped.borrow()
     }
@@ -136,16 +553,44 @@ impl GherkinEnv {
     }
 }
 
+/// Checks whether the `c`haracter is trimmed around table cells, as upstream
+/// does for any whitespace. A newline in a cell can only come from an escape
+/// sequence, so it's kept.
+fn is_cell_padding(c: char) -> bool {
+    c.is_whitespace() && c != '\n'
+}
+
+/// Returns the span of a node from the start of its `header` to the end of the
+/// last of its `children`, like the steps of a scenario.
+fn extent(header: Span, children: impl IntoIterator<Item = Span>) -> Span {
+    let end = children
+        .into_iter()
+        .map(|s| s.end)
+        .fold(header.end, usize::max);
+    Span {
+        start: header.start,
+        end,
+    }
+}
+
 impl Default for GherkinEnv {
     fn default() -> Self {
         GherkinEnv {
             keywords: RefCell::new(Keywords::default()),
+            language: RefCell::new("en".into()),
+            mode: ParseMode::default(),
+            language_policy: LanguagePolicy::default(),
             last_error: RefCell::new(None),
             fatal_error: RefCell::new(None),
             last_step: RefCell::new(None),
             last_keyword: RefCell::new(None),
             line_offsets: RefCell::new(vec![0]),
+            source: RefCell::new(String::new()),
+            comments: RefCell::new(vec![]),
+            id_generator: RefCell::new(Box::new(IncrementingIdGenerator::default())),
             was_escaped: RefCell::new(false),
+            markdown: RefCell::new(false),
+            untitled: RefCell::new(None),
         }
     }
 }
@@ -163,23 +608,20 @@ rule nl() = quiet!{comment_no_nl()? nl0() p:position!() {
     env.increment_nl(p);
 }}
 rule eof() = quiet!{![_]}
+rule source() = s:$([_]*) { env.set_source(s) }
 rule nl_eof() = quiet!{(nl() / [' ' | '\t'])+ / eof()}
-rule comment_no_nl() = quiet!{[' ' | '\t']* "#" $((!nl0()[_])*)}
+rule comment_no_nl() = quiet!{!([' ' | '\t']* untitled()) p:position!() t:$([' ' | '\t']* "#" (!nl0()[_])*) {
+    env.add_comment(t, p);
+}}
 rule comment() = quiet!{comment_no_nl() nl_eof()}
 rule not_nl() -> &'input str = n:$((!nl0()[_])+) { n }
 
 rule keyword1(list: &[&str]) -> &'input str
-    = input:$([_]*<
-        {list.iter().map(|x| x.chars().count()).min().unwrap()},
-        {list.iter().map(|x| x.chars().count()).max().unwrap()}
-    >) {?
-        // println!("Input: {}", &input);
-        if let Some(v) = list.iter().find(|x| input.starts_with(**x)) {
-            env.set_keyword((*v).to_string());
-            // println!("Found: {}", &v);
+    = input:$((!nl0() [_])*) {?
+        if let Some((v, len)) = env.match_keyword(list, input) {
+            env.set_keyword(v.to_string(), len);
             Err("success")
         } else {
-            // println!("Unfound: {}", &input);
             env.clear_keyword();
             env.set_last_error(EnvError::UnknownKeyword(input.into()));
             Err("unknown keyword")
@@ -189,155 +631,163 @@ rule keyword1(list: &[&str]) -> &'input str
 rule keyword0(list: &[&str]) -> usize
     = keyword1(list)? {?
         match env.last_keyword().as_ref() {
-            Some(v) => Ok(v.chars().count()),
+            Some((_, len)) => Ok(*len),
             None => Err("no match")
         }
     }
 
+rule forgiving() = {?
+    if env.mode() == ParseMode::Forgiving {
+        Ok(())
+    } else {
+        Err("forgiving mode")
+    }
+}
+
+rule title_colon()
+    = forgiving() ((_ ":")+ / &([' ' | '\t'] / nl0() / eof()))
+    / ":"
+
+rule stray_colon() = forgiving() ":" _
+
 pub(crate) rule keyword<'a>(list: &[&'a str]) -> &'a str
     = comment()* len:keyword0(list) [_]*<{len}> {
-        let kw = env.take_keyword();
+        let (kw, _) = env.take_keyword();
         list.iter().find(|x| **x == &*kw).unwrap()
     }
 
-rule language_directive() -> ()
-    = ([' ' | '\t'] / nl_no_comment())* "#" _ "language" _ ":" _ l:$(not_nl()+) _ nl() {?
-        env.set_language(l)
+rule language_directive() -> LanguageDirective
+    = (!(_ "#" _ "language" _ ":") (nl() / [' ' | '\t']))* _ pa:position!()
+      "#" _ "language" _ ":" _ l:$(not_nl()+) pb:position!() _ nl() {?
+        env.set_language(l).map(|()| {
+            LanguageDirective::builder()
+                .language(l.into())
+                .span(Span { start: pa, end: pb })
+                .position(env.position(pa))
+                .build()
+        })
+    }
+
+rule markdown() = {? if *env.markdown.borrow() { Ok(()) } else { Err("markdown") } }
+
+rule untitled() = p:position!() {?
+    if *env.untitled.borrow() == Some(p) { Ok(()) } else { Err("feature") }
+}
+
+rule feature_keyword() -> &'static str
+    = untitled() { "" }
+    / k:keyword((env.keywords().feature)) title_colon() { k }
+
+rule fence(delimiter: &str) = s:$("`"+) &(_ (nl0() / eof())) {?
+    if s == delimiter {
+        Ok(())
+    } else {
+        Err("fence")
+    }
+}
+
+rule fenced(is_fenced: bool) = {? if is_fenced { Ok(()) } else { Err("fenced") } }
+
+rule delimiter(d: &str) = s:$([_]*<{d.chars().count()}>) {?
+    if s == d {
+        Ok(())
+    } else {
+        Err("delimiter")
+    }
+}
+
+rule docstring_close(d: &str, is_fenced: bool)
+    = fenced(is_fenced) fence(d)
+    / !fenced(is_fenced) delimiter(d) not_nl()?
+
+rule docstring_line(d: &str, is_fenced: bool) -> (usize, &'input str)
+    = !(_ docstring_close(d, is_fenced)) pa:position!() l:$((!nl0() [_])*) nl_no_comment() {
+        (pa, l)
     }
 
-rule docstring() -> String
-    = "\"\"\"" n:$((!"\"\"\"" (nl() / [_]))*) "\"\"\"" nl_eof() {
-        textwrap::dedent(n)
+rule docstring() -> DocString
+    = markdown() pa:position!() d:$("```" "`"*) m:not_nl()? nl_no_comment()
+      l:docstring_line(d, true)* _ docstring_close(d, true) pb:position!() nl_eof()
+    {
+        env.docstring(d, m, &l, Span { start: pa, end: pb })
     }
-    / "```" n:$((!"```"(nl() / [_]))*) "```" nl_eof() {
-        textwrap::dedent(n)
+    / pa:position!() d:$("\"\"\"" / "```") m:not_nl()? nl_no_comment()
+      l:docstring_line(d, false)* _ docstring_close(d, false) pb:position!() nl_eof()
+    {
+        env.docstring(d, m, &l, Span { start: pa, end: pb })
     }
 
 rule escaped_cell_char() -> &'input str
     = "\\n" { "\n" } / "\\|" { "|" } / "\\\\" { "\\" }
+    // Upstream keeps the backslash of an unknown escape sequence, leaving the
+    // character following it to be taken on its own.
+    / "\\" !nl0() { "\\" }
 
-rule table_cell() -> Vec<&'input str>
-    = "|" _ !(nl0() / eof()) n:((escaped_cell_char() / $(!("|" / "\\" / nl0())[_]))*) { n }
+rule table_cell_char() -> (&'input str, usize, usize)
+    = pa:position!() c:(escaped_cell_char() / $(!("|" / "\\" / nl0())[_])) pb:position!() {
+        (c, pa, pb)
+    }
 
-pub(crate) rule table_row() -> Vec<String>
-    = n:(table_cell() ** _) _ "|" _ nl_eof() {
-        n.into_iter()
-            .map(|s| {
-                let Some((start, first)) = s.iter().enumerate()
-                    .find_map(|(n, s)| {
-                        let trimmed = s.trim_start_matches([' ', '\t']);
-                        (!trimmed.is_empty()).then_some((n, trimmed))
-                    }) else { return String::new() };
-                let Some((end, last)) = s.iter().enumerate().rev()
-                    .find_map(|(n, s)| {
-                        let trimmed = s.trim_end_matches([' ', '\t']);
-                        (!trimmed.is_empty()).then_some((n, trimmed))
-                    }) else { return String::new() };
-
-                if start == end {
-                    first.trim_end_matches([' ', '\t']).to_owned()
-                } else {
-                    iter::once(first)
-                        .chain(
-                            s.into_iter().skip(start + 1)
-                                .take(end.saturating_sub(start + 1))
-                        )
-                        .chain(iter::once(last))
-                        .collect::<String>()
-                }
-            })
-            .collect()
+rule table_cell() -> TableCell
+    = "|" _ !(nl0() / eof()) pa:position!() c:table_cell_char()* {
+        env.table_cell(&c, pa)
     }
 
-pub(crate) rule table0() -> Vec<Vec<String>>
+pub(crate) rule table_row() -> TableRow
+    = pa:position!() c:(table_cell() ** _) _ "|" pb:position!() _ nl_eof() {
+        TableRow::builder()
+            .cells(c)
+            .span(Span { start: pa, end: pb })
+            .position(env.position(pa))
+            .build()
+    }
+
+pub(crate) rule table0() -> Vec<TableRow>
     = _ d:(table_row() ++ _) {
         if d.is_empty() {
             d
         } else {
-            let len = d[0].len();
-            d.into_iter().map(|mut x| { x.truncate(len); x }).collect()
+            let len = d[0].cells.len();
+            d.into_iter().map(|mut x| { x.cells.truncate(len); x }).collect()
         }
     }
 
 pub(crate) rule table() -> Table
-    = pa:position!() t:table0() pb:position!() {?
-        if !t.is_empty() && t.iter().skip(1).any(|x| x.len() != t[0].len()) {
-            env.set_fatal_error(EnvError::InconsistentCellCount(t));
+    = t:table0() {?
+        if !t.is_empty() && t.iter().skip(1).any(|x| x.cells.len() != t[0].cells.len()) {
+            env.set_fatal_error(EnvError::InconsistentCellCount(
+                t.iter().map(|r| r.values().into_iter().map(Into::into).collect()).collect(),
+            ));
             Err("inconsistent table row sizes")
         } else {
+            // The span ends with the last row, before its line break.
+            let span = match (t.first(), t.last()) {
+                (Some(first), Some(last)) => Span { start: first.span.start, end: last.span.end },
+                _ => Span::default(),
+            };
             Ok(Table::builder()
-                .span(Span { start: pa, end: pb })
-                .position(env.position(pa))
+                .span(span)
+                .position(env.position(span.start))
                 .rows(t)
                 .build())
         }
     }
 
 pub(crate) rule step() -> Step
-    = comment()* pa:position!() k:keyword((env.keywords().given)) _ n:not_nl() pb:position!() _ nl_eof() _
-      d:docstring()? t:table()?
-    {
-        env.set_last_step(StepType::Given);
-        Step::builder().ty(StepType::Given)
-            .keyword(k.to_string())
-            .value(n.trim_end().to_string())
-            .table(t)
-            .docstring(d)
-            .span(Span { start: pa, end: pb })
-            .position(env.position(pa))
-            .build()
-    }
-    / pa:position!() k:keyword((env.keywords().when)) _ n:not_nl() pb:position!() _ nl_eof() _
+    = comment()* pa:position!() k:keyword((&env.keywords().steps())) _ stray_colon()? n:not_nl()? pb:position!() _ nl_eof() _
       d:docstring()? t:table()?
     {
-        env.set_last_step(StepType::When);
-        Step::builder().ty(StepType::When)
-            .keyword(k.to_string())
-            .value(n.trim_end().to_string())
-            .table(t)
-            .docstring(d)
-            .span(Span { start: pa, end: pb })
-            .position(env.position(pa))
-            .build()
-    }
-    / pa:position!() k:keyword((env.keywords().then)) _ n:not_nl() pb:position!() _ nl_eof() _
-      d:docstring()? t:table()?
-    {
-        env.set_last_step(StepType::Then);
-        Step::builder().ty(StepType::Then)
-            .keyword(k.to_string())
-            .value(n.trim_end().to_string())
-            .table(t)
-            .docstring(d)
-            .span(Span { start: pa, end: pb })
-            .position(env.position(pa))
-            .build()
-    }
-    / pa:position!() k:keyword((env.keywords().and)) _ n:not_nl() pb:position!() _ nl_eof() _
-      d:docstring()? t:table()?
-    {
-        let ty = env.last_step().unwrap_or(StepType::Given);
-        env.set_last_step(ty);
-        Step::builder().ty(ty)
-            .keyword(k.to_string())
-            .value(n.trim_end().to_string())
-            .table(t)
-            .docstring(d)
-            .span(Span { start: pa, end: pb })
-            .position(env.position(pa))
-            .build()
-    }
-    / pa:position!() k:keyword((env.keywords().but)) _ n:not_nl() pb:position!() _ nl_eof() _
-      d:docstring()? t:table()?
-    {
-        let ty = env.last_step().unwrap_or(StepType::Given);
-        env.set_last_step(ty);
+        let (ty, keyword_type) = env.step_type(k);
+        let header = env.header_span(pa, pb);
+        let span = extent(header, d.iter().map(|d| d.span).chain(t.iter().map(|t| t.span)));
         Step::builder().ty(ty)
+            .keyword_type(keyword_type)
             .keyword(k.to_string())
-            .value(n.trim_end().to_string())
+            .value(n.unwrap_or_default().trim_end().to_string())
             .table(t)
             .docstring(d)
-            .span(Span { start: pa, end: pb })
+            .header_span(header)
+            .span(span)
             .position(env.position(pa))
             .build()
     }
@@ -350,17 +800,21 @@ pub(crate) rule steps() -> Vec<Step>
 
 rule background() -> Background
     = comment()* _ pa:position!()
-      k:keyword((env.keywords().background)) ":" _ n:not_nl()? nl_eof()
-      d:description((&env.keywords().excluded_background()))?
+      k:keyword((env.keywords().background)) title_colon() _ n:not_nl()? ph:position!() nl_eof()
+      d:description((&env.keywords().excluded_background()), (&env.keywords().steps()))?
       s:steps()?
-      pb:position!()
     {
+        let d = d.flatten();
+        let s = s.unwrap_or_default();
+        let header = env.header_span(pa, ph);
+        let span = extent(header, d.iter().map(|d| d.span).chain(s.iter().map(|s| s.span)));
         Background::builder()
             .keyword(k.into())
             .name(n.unwrap_or_default().trim_end().to_string())
-            .description(d.flatten())
-            .steps(s.unwrap_or_default())
-            .span(Span { start: pa, end: pb })
+            .description(d)
+            .steps(s)
+            .header_span(header)
+            .span(span)
             .position(env.position(pa))
             .build()
     }
@@ -370,22 +824,17 @@ rule any_directive() -> &'static str
         k
     }
 
-rule description_line(excluded: &[&str]) -> &'input str
+rule description_line(titles: &[&str], lines: &[&str]) -> (usize, usize)
     = _
-      !"@" !keyword((excluded))
-      _ n:not_nl() nl_eof()
+      !"@" !(keyword((titles)) title_colon()) !keyword((lines))
+      _ pa:position!() not_nl() pb:position!() nl_eof()
     {
-        n
+        (pa, pb)
     }
 
-rule description(excluded: &[&str]) -> Option<String>
-    = d:(description_line(excluded) ** _) __ {
-        let d = d.join("\n");
-        if d.trim() == "" {
-            None
-        } else {
-            Some(d)
-        }
+rule description(titles: &[&str], lines: &[&str]) -> Option<Description>
+    = d:(description_line(titles, lines) ** _) __ {
+        env.description(&d)
     }
 
 rule examples() -> Examples
@@ -394,18 +843,21 @@ rule examples() -> Examples
       t:tags()
       _
       pa:position!()
-      k:keyword((env.keywords().examples)) ":" _ n:not_nl()? nl_eof()
-      d:description((&env.keywords().excluded_examples()))?
+      k:keyword((env.keywords().examples)) title_colon() _ n:not_nl()? ph:position!() nl_eof()
+      d:description((&env.keywords().excluded_examples()), (&["|"]))?
       tb:table()?
-      pb:position!()
     {
+        let d = d.flatten();
+        let header = env.header_span(pa, ph);
+        let span = extent(header, d.iter().map(|d| d.span).chain(tb.iter().map(|t| t.span)));
         Examples::builder()
             .keyword(k.into())
             .name(n.map(|n| n.trim_end().to_string()))
-            .description(d.flatten())
+            .description(d)
             .tags(t)
             .table(tb)
-            .span(Span { start: pa, end: pb })
+            .header_span(header)
+            .span(span)
             .position(env.position(pa))
             .build()
     }
@@ -416,20 +868,31 @@ rule scenario() -> Scenario
       t:tags()
       _
       pa:position!()
-      k:keyword((env.keywords().scenario)) ":" _ n:not_nl()? _ nl_eof()
-      d:description((&env.keywords().excluded_scenario()))?
+      k:keyword((env.keywords().scenario)) title_colon() _ n:not_nl()? ph:position!() _ nl_eof()
+      d:description((&env.keywords().excluded_scenario()), (&env.keywords().steps()))?
       s:steps()?
       e:examples()*
-      pb:position!()
     {
+        let d = d.flatten();
+        let s = s.unwrap_or_default();
+        let header = env.header_span(pa, ph);
+        let span = extent(
+            header,
+            d.iter()
+                .map(|d| d.span)
+                .chain(s.iter().map(|s| s.span))
+                .chain(e.iter().map(|e| e.span)),
+        );
         Scenario::builder()
             .keyword(k.into())
+            .kind(ScenarioKind::Scenario)
             .name(n.unwrap_or_default().trim_end().to_string())
-            .description(d.flatten())
+            .description(d)
             .tags(t)
-            .steps(s.unwrap_or_default())
+            .steps(s)
             .examples(e)
-            .span(Span { start: pa, end: pb })
+            .header_span(header)
+            .span(span)
             .position(env.position(pa))
             .build()
     }
@@ -438,20 +901,31 @@ rule scenario() -> Scenario
       t:tags()
       _
       pa:position!()
-      k:keyword((env.keywords().scenario_outline)) ":" _ n:not_nl()? _ nl_eof()
-      d:description((&env.keywords().excluded_scenario_outline()))?
+      k:keyword((env.keywords().scenario_outline)) title_colon() _ n:not_nl()? ph:position!() _ nl_eof()
+      d:description((&env.keywords().excluded_scenario_outline()), (&env.keywords().steps()))?
       s:steps()?
       e:examples()*
-      pb:position!()
     {
+        let d = d.flatten();
+        let s = s.unwrap_or_default();
+        let header = env.header_span(pa, ph);
+        let span = extent(
+            header,
+            d.iter()
+                .map(|d| d.span)
+                .chain(s.iter().map(|s| s.span))
+                .chain(e.iter().map(|e| e.span)),
+        );
         Scenario::builder()
             .keyword(k.into())
+            .kind(ScenarioKind::ScenarioOutline)
             .name(n.unwrap_or_default().trim_end().to_string())
-            .description(d.flatten())
+            .description(d)
             .tags(t)
-            .steps(s.unwrap_or_default())
+            .steps(s)
             .examples(e)
-            .span(Span { start: pa, end: pb })
+            .header_span(header)
+            .span(span)
             .position(env.position(pa))
             .build()
     }
@@ -466,8 +940,14 @@ rule tag_char() -> &'input str
         }
     }
 
-pub(crate) rule tag() -> String
-    = "@" s:tag_char()+ { s.join("") }
+pub(crate) rule tag() -> Tag
+    = pa:position!() "@" s:tag_char()+ pb:position!() {
+        Tag::builder()
+            .name(s.join(""))
+            .span(Span { start: pa, end: pb })
+            .position(env.position(pa))
+            .build()
+    }
 
 rule tag_in_expr_char() -> Option<&'input str>
     = s:$([_]) {?
@@ -499,7 +979,7 @@ pub(crate) rule tag_in_expr() -> String
         }
     }
 
-pub(crate) rule tags() -> Vec<String>
+pub(crate) rule tags() -> Vec<Tag>
     = t:(tag() ** __) _ nl()* { t }
     / { vec![] }
 
@@ -508,21 +988,31 @@ rule rule_() -> Rule
       t:tags()
       _
       pa:position!()
-      k:keyword((env.keywords().rule)) ":" _ n:not_nl()? _ nl_eof()
-      d:description((&env.keywords().excluded_rule()))?
+      k:keyword((env.keywords().rule)) title_colon() _ n:not_nl()? ph:position!() _ nl_eof()
+      d:description((&env.keywords().excluded_rule()), (&[]))?
       b:background()? nl()*
       s:scenarios()? nl()*
     //   e:examples()?
-      pb:position!()
     {
+        let d = d.flatten();
+        let s = s.unwrap_or_default();
+        let header = env.header_span(pa, ph);
+        let span = extent(
+            header,
+            d.iter()
+                .map(|d| d.span)
+                .chain(b.iter().map(|b| b.span))
+                .chain(s.iter().map(|s| s.span)),
+        );
         Rule::builder()
             .keyword(k.into())
             .name(n.unwrap_or_default().trim_end().to_string())
-            .description(d.flatten())
+            .description(d)
             .tags(t)
             .background(b)
-            .scenarios(s.unwrap_or_default())
-            .span(Span { start: pa, end: pb })
+            .scenarios(s)
+            .header_span(header)
+            .span(span)
             .position(env.position(pa))
             .build()
     }
@@ -533,33 +1023,85 @@ rule rules() -> Vec<Rule>
 pub(crate) rule scenarios() -> Vec<Scenario>
     = _ s:(scenario() ** _)? { s.unwrap_or_default() }
 
+pub(crate) rule document() -> GherkinDocument
+    = &source() _ l:language_directive()? __ f:feature_definition()? {? env.document(l, f) }
+
 pub(crate) rule feature() -> Feature
-    = _ language_directive()?
-      nl()*
-      t:tags()
+    = d:document() {? d.feature.ok_or("feature") }
+
+rule feature_definition() -> Feature
+    = t:tags()
+      _
       pa:position!()
-      k:keyword((env.keywords().feature)) ":" _ n:not_nl()? _ nl_eof()
-      d:description((&env.keywords().excluded_feature()))?
+      k:feature_keyword() _ n:not_nl()? ph:position!() _ nl_eof()
+      d:description((&env.keywords().excluded_feature()), (&[]))?
       b:background()? nl()*
       s:scenarios() nl()*
-      r:rules() pb:position!()
+      r:rules()
       nl()*
-    {?
-        if let Err(e) = env.assert_no_error() {
-            Err(e)
-        } else {
-            Ok(Feature::builder()
-                .keyword(k.into())
-                .tags(t)
-                .name(n.unwrap_or_default().trim_end().to_string())
-                .description(d.flatten())
-                .background(b)
-                .scenarios(s)
-                .rules(r)
-                .span(Span { start: pa, end: pb })
-                .position(env.position(pa))
-                .build())
-        }
+    {
+        let d = d.flatten();
+        let header = env.header_span(pa, ph);
+        let span = extent(
+            header,
+            d.iter()
+                .map(|d| d.span)
+                .chain(b.iter().map(|b| b.span))
+                .chain(s.iter().map(|s| s.span))
+                .chain(r.iter().map(|r| r.span)),
+        );
+        Feature::builder()
+            .keyword(k.into())
+            .tags(t)
+            .name(n.unwrap_or_default().trim_end().to_string())
+            .description(d)
+            .background(b)
+            .scenarios(s)
+            .rules(r)
+            .language(env.language())
+            .header_span(header)
+            .span(span)
+            .position(env.position(pa))
+            .build()
+    }
+
+rule fragment_start() = &source() _ language_directive()? __
+
+rule fragment_end() = __ {? env.assert_no_error() }
+
+pub(crate) rule step_fragment() -> Step
+    = fragment_start() s:step() fragment_end() {
+        let mut s = s;
+        env.assign_step_ids(&mut s);
+        s
+    }
+
+pub(crate) rule background_fragment() -> Background
+    = fragment_start() b:background() fragment_end() {
+        let mut b = b;
+        env.assign_background_ids(&mut b);
+        b
+    }
+
+pub(crate) rule scenario_fragment() -> Scenario
+    = fragment_start() s:scenario() fragment_end() {
+        let mut s = s;
+        env.assign_scenario_ids(&mut s);
+        s
+    }
+
+pub(crate) rule examples_fragment() -> Examples
+    = fragment_start() e:examples() fragment_end() {
+        let mut e = e;
+        env.assign_examples_ids(&mut e);
+        e
+    }
+
+pub(crate) rule table_fragment() -> Table
+    = fragment_start() t:table() fragment_end() {
+        let mut t = t;
+        env.assign_table_ids(&mut t);
+        t
     }
 
 pub(crate) rule tag_operation() -> TagOperation = precedence!{
@@ -577,9 +1119,10 @@ pub(crate) rule tag_operation() -> TagOperation = precedence!{
 
 #[cfg(test)]
 mod test {
-    use std::{collections::HashMap, fs};
+    use std::fs;
 
     use super::*;
+    use crate::{pickle::Pickle, FeatureChild, ParseError, RuleChild, UuidIdGenerator};
 
     const FOO: &str = "# language: formal\r\n
 @hot-stuff
@@ -818,6 +1361,469 @@ Feature: First step with Besides
         assert_eq!(feature.scenarios[0].steps.len(), 0);
     }
 
+    #[test]
+    fn description_starting_with_keyword() {
+        let env = GherkinEnv::default();
+        let input = r#"
+Feature: Foo
+  Examples of things this feature does not do
+  Rules are there to be followed
+
+  Scenario: Bar
+    Given a step
+"#;
+        let feature = gherkin_parser::feature(input, &env).unwrap();
+        assert_eq!(
+            feature.description.unwrap(),
+            "  Examples of things this feature does not do\n  Rules are there to be followed",
+        );
+        assert_eq!(feature.scenarios.len(), 1);
+    }
+
+    #[test]
+    fn docstring_with_separator_inside_line() {
+        let env = GherkinEnv::default();
+        let input = r#"
+Feature: Foo
+  Scenario: Bar
+    Given a step
+      """
+      a """ in the middle
+      """
+    And a step without text
+"#;
+        let feature = gherkin_parser::feature(input, &env).unwrap();
+        let steps = &feature.scenarios[0].steps;
+        assert_eq!(
+            steps[0].docstring(),
+            Some(&"a \"\"\" in the middle".to_owned()),
+        );
+        assert_eq!(steps[1].value, "a step without text");
+    }
+
+    #[test]
+    fn docstring_is_dedented_relative_to_delimiter() {
+        let input = "Feature: Foo\n  Scenario: Bar\n    Given a step\n      \"\"\"json\n        {\n      \\\"\\\"\\\"\n    }\n      \"\"\"\n";
+        let feature = Feature::parse(input, GherkinEnv::default()).unwrap();
+
+        let docstring = feature.scenarios[0].steps[0].docstring.as_ref().unwrap();
+        assert_eq!(docstring.content, "  {\n\"\"\"\n}");
+        assert_eq!(docstring.delimiter, "\"\"\"");
+        assert_eq!(docstring.media_type.as_deref(), Some("json"));
+        let media_type = docstring.media_type_span.unwrap();
+        assert_eq!(&input[media_type.start..media_type.end], "json");
+        assert_eq!(docstring.position, LineCol { line: 4, col: 7 });
+        assert_eq!(
+            &input[docstring.span.start..docstring.span.end],
+            "\"\"\"json\n        {\n      \\\"\\\"\\\"\n    }\n      \"\"\"",
+        );
+        let lines = docstring
+            .line_spans
+            .iter()
+            .map(|s| &input[s.start..s.end])
+            .collect::<Vec<_>>();
+        assert_eq!(lines, ["  {", "\\\"\\\"\\\"", "}"]);
+    }
+
+    #[test]
+    fn table_cells() {
+        let input = "  | æ |  b\\|  |\n  |   | c |\n";
+        let table = Table::parse(input, GherkinEnv::default()).unwrap();
+
+        assert_eq!(table.values(), [["æ", "b|"], ["", "c"]]);
+        let row = &table.rows[0];
+        assert_eq!(row.position, LineCol { line: 1, col: 3 });
+        assert_eq!(&input[row.span.start..row.span.end], "| æ |  b\\|  |");
+        let cell = &row.cells[1];
+        assert_eq!(cell.position, LineCol { line: 1, col: 10 });
+        assert_eq!(&input[cell.span.start..cell.span.end], "b\\|");
+        assert_eq!(table.rows[1].cells[0].position, LineCol { line: 2, col: 7 });
+        assert_eq!(table.rows[1].cells[1], "c");
+    }
+
+    #[test]
+    fn step_keyword_types() {
+        let input = "Feature: Foo\n  Scenario: Bar\n    * a\n    When b\n    * c\n    And d\n    Then e\n    But f\n    Given g\n";
+        let feature = Feature::parse(input, GherkinEnv::default()).unwrap();
+
+        assert_eq!(
+            feature.scenarios[0]
+                .steps
+                .iter()
+                .map(|s| (s.ty, s.keyword_type))
+                .collect::<Vec<_>>(),
+            [
+                (StepType::Given, StepKeywordType::Unknown),
+                (StepType::When, StepKeywordType::Action),
+                (StepType::When, StepKeywordType::Unknown),
+                (StepType::When, StepKeywordType::Conjunction),
+                (StepType::Then, StepKeywordType::Outcome),
+                (StepType::Then, StepKeywordType::Conjunction),
+                (StepType::Given, StepKeywordType::Precondition),
+            ],
+        );
+    }
+
+    #[test]
+    fn scenario_kinds() {
+        let input = "Feature: Foo\n  Scenario Outline: A\n    Given a <x>\n\n  Example: B\n    Given a <x>\n    Examples:\n      | x |\n\n  Scenario: C\n    Given a step\n      \"\"\"\n      <x>\n      \"\"\"\n\n  Scenario: D\n    Given 1 < 2\n";
+        let feature = Feature::parse(input, GherkinEnv::default()).unwrap();
+
+        assert_eq!(
+            feature
+                .scenarios
+                .iter()
+                .map(|s| (s.kind, s.is_templated()))
+                .collect::<Vec<_>>(),
+            [
+                (ScenarioKind::ScenarioOutline, true),
+                (ScenarioKind::Scenario, true),
+                (ScenarioKind::Scenario, true),
+                (ScenarioKind::Scenario, false),
+            ],
+        );
+    }
+
+    #[test]
+    fn comments() {
+        let input = "# top\n@tag\nFeature: Foo\n  # in description\n  Scenario: Bar\n    Given a step # not a comment\n      \"\"\"\n      # docstring\n      \"\"\"\n    # between steps\n";
+        let document = GherkinDocument::parse(input, GherkinEnv::default()).unwrap();
+
+        assert_eq!(
+            document
+                .comments
+                .iter()
+                .map(|c| (c.text.as_str(), c.position.line, c.position.col))
+                .collect::<Vec<_>>(),
+            [
+                ("# top", 1, 1),
+                ("  # in description", 4, 1),
+                ("    # between steps", 10, 1),
+            ],
+        );
+        let comment = &document.comments[1];
+        assert_eq!(&input[comment.span.start..comment.span.end], comment.text);
+        assert_eq!(
+            document.feature.unwrap().scenarios[0].steps[0].docstring(),
+            Some(&"# docstring".to_owned()),
+        );
+    }
+
+    #[test]
+    fn documents_without_feature() {
+        for (input, end) in [
+            ("", 2),
+            ("\n  \n", 3),
+            ("# language: fr\n", 2),
+            ("# Just a comment", 2),
+        ] {
+            let document = GherkinDocument::parse(input, GherkinEnv::default()).unwrap();
+            assert_eq!(document.feature, None, "{:?}", input);
+
+            let error = Feature::parse(input, GherkinEnv::default()).unwrap_err();
+            assert_eq!(error.expected(), ["feature"]);
+            assert_eq!(error.position(), LineCol { line: end, col: 1 });
+        }
+
+        let document = GherkinDocument::parse("# language: fr\n", GherkinEnv::default()).unwrap();
+        assert_eq!(document.language, "fr");
+        assert!(document.comments.is_empty());
+        assert!(GherkinDocument::parse("# language: xx\n", GherkinEnv::default()).is_err());
+        assert!(GherkinDocument::parse("@tag\n", GherkinEnv::default()).is_err());
+    }
+
+    #[test]
+    fn document_path() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/data/good/empty.feature"
+        );
+        let document = GherkinDocument::parse_path(path, GherkinEnv::default()).unwrap();
+        assert_eq!(document.uri.as_deref(), Some(Path::new(path)));
+        assert_eq!(document.feature, None);
+
+        let path = path.replace("empty", "minimal");
+        let document = GherkinDocument::parse_path(&path, GherkinEnv::default()).unwrap();
+        let feature = document.feature.unwrap();
+        assert_eq!(feature.path.as_deref(), Some(Path::new(&path)));
+        assert_eq!(
+            Feature::parse_path(&path, GherkinEnv::default()).unwrap(),
+            feature
+        );
+    }
+
+    #[test]
+    fn descriptions() {
+        let input = "Feature: Foo\n\n  Some *Markdown*:\n\n  - a list\n    - nested  \n  \
+                     # a comment\n  ```\n  code\n  ```\n\n  Scenario: Bar\n";
+        let feature = Feature::parse(input, GherkinEnv::default()).unwrap();
+
+        let description = feature.description.unwrap();
+        assert_eq!(
+            description,
+            "  Some *Markdown*:\n\n  - a list\n    - nested  \n  ```\n  code\n  ```",
+        );
+        assert_eq!(description.position, LineCol { line: 3, col: 1 });
+        assert!(input[description.span.start..].starts_with("  Some"));
+        assert!(input[..description.span.end].ends_with("  ```"));
+        assert_eq!(feature.scenarios[0].description, None);
+    }
+
+    #[test]
+    fn tags() {
+        let input =
+            "@a @b\nFeature: Foo\n  @c\n  Scenario: Bar\n    Given a step\n\n  @d\n  Rule: Baz\n";
+        let feature = Feature::parse(input, GherkinEnv::default()).unwrap();
+
+        assert_eq!(feature.tag_names().collect::<Vec<_>>(), ["a", "b"]);
+        let tag = &feature.tags[1];
+        assert_eq!(tag.position, LineCol { line: 1, col: 4 });
+        assert_eq!(&input[tag.span.start..tag.span.end], "@b");
+        assert_eq!(tag.to_string(), "@b");
+        assert_eq!(feature.scenarios[0].tags, ["c"]);
+        assert_eq!(
+            feature.rules[0].tags[0].position,
+            LineCol { line: 7, col: 3 }
+        );
+
+        let ids = feature
+            .tags
+            .iter()
+            .chain(&feature.scenarios[0].tags)
+            .chain(&feature.rules[0].tags)
+            .map(|t| t.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["5", "6", "1", "3"]);
+    }
+
+    #[test]
+    fn language() {
+        let input = "\n  # language: fr\nFonctionnalité: Foo\n";
+        let document = GherkinDocument::parse(input, GherkinEnv::default()).unwrap();
+
+        assert_eq!(document.language, "fr");
+        assert_eq!(document.feature.unwrap().language, "fr");
+        let directive = document.language_directive.unwrap();
+        assert_eq!(directive.language, "fr");
+        assert_eq!(directive.position, LineCol { line: 2, col: 3 });
+        assert_eq!(
+            &input[directive.span.start..directive.span.end],
+            "# language: fr"
+        );
+
+        // Like upstream, comments and blank lines may come before the directive.
+        let input = "# hello\n\n# language: fr\nFonctionnalité: Foo\n";
+        for mode in [ParseMode::Lenient, ParseMode::Strict] {
+            let env = GherkinEnv::default().with_mode(mode);
+            let document = GherkinDocument::parse(input, env).unwrap();
+            assert_eq!(document.language, "fr", "{:?}", mode);
+            assert_eq!(document.language_directive.unwrap().position.line, 3);
+            assert_eq!(document.comments.len(), 1, "{:?}", mode);
+            assert_eq!(document.comments[0].text, "# hello");
+        }
+
+        let document = GherkinDocument::parse("Feature: Foo\n", GherkinEnv::default()).unwrap();
+        assert_eq!(document.language, "en");
+        assert_eq!(document.language_directive, None);
+
+        let env = GherkinEnv::default().with_language_policy(LanguagePolicy::new().detect(true));
+        let feature = Feature::parse("Fonctionnalité: Foo\n", env).unwrap();
+        assert_eq!(feature.language, "fr");
+    }
+
+    #[test]
+    fn children() {
+        let input = "Feature: Foo\n  Background:\n  Scenario: Bar\n  Rule: Baz\n    \
+                     Background:\n    Scenario: Qux\n";
+        let feature = Feature::parse(input, GherkinEnv::default()).unwrap();
+
+        let rule = &feature.rules[0];
+        assert_eq!(
+            feature.children().collect::<Vec<_>>(),
+            [
+                FeatureChild::Background(feature.background.as_ref().unwrap()),
+                FeatureChild::Scenario(&feature.scenarios[0]),
+                FeatureChild::Rule(rule),
+            ],
+        );
+        assert_eq!(
+            rule.children().collect::<Vec<_>>(),
+            [
+                RuleChild::Background(rule.background.as_ref().unwrap()),
+                RuleChild::Scenario(&rule.scenarios[0]),
+            ],
+        );
+    }
+
+    #[test]
+    fn effective_scenarios() {
+        let input = "@a\nFeature: Foo\n  Background:\n    Given a\n  @b\n  Scenario: Bar\n    \
+                     Given b\n  @c\n  Rule: Baz\n    Background:\n      Given c\n    @a\n    \
+                     Scenario: Qux\n      Given d\n  Rule: Quux\n    Scenario: Corge\n";
+        let feature = Feature::parse(input, GherkinEnv::default()).unwrap();
+
+        let scenarios = feature.effective_scenarios().collect::<Vec<_>>();
+        let summary = scenarios
+            .iter()
+            .map(|s| {
+                (
+                    s.scenario.name.as_str(),
+                    s.rule.map(|r| r.name.as_str()),
+                    s.tag_names().collect::<Vec<_>>(),
+                    s.steps().map(|s| s.value.as_str()).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                ("Bar", None, vec!["a", "b"], vec!["a", "b"]),
+                ("Qux", Some("Baz"), vec!["a", "c", "a"], vec!["a", "c", "d"]),
+                ("Corge", Some("Quux"), vec!["a"], vec!["a"]),
+            ],
+        );
+        assert_eq!(scenarios[1].scenario, &feature.rules[0].scenarios[0]);
+        assert_eq!(scenarios[1].background_steps.len(), 2);
+    }
+
+    #[test]
+    fn spans() {
+        let input = "Feature: Foo  \n  Description\n\n  Background:\n    Given a step\n\n  \
+                     Rule: Bar\n    @tag\n    Scenario Outline: Baz\n      Given a <x>  \n        \
+                     \"\"\"\n        text\n        \"\"\"\n      When a table\n        | a |\n\n      \
+                     Examples: Qux\n        | x |\n        | 1 |\n\n# A comment\n";
+        let feature = Feature::parse(input, GherkinEnv::default()).unwrap();
+        let text = |span: Span| &input[span.start..span.end];
+
+        assert_eq!(text(feature.header_span), "Feature: Foo");
+        assert!(text(feature.span).starts_with("Feature: Foo  \n  Description"));
+        assert!(text(feature.span).ends_with("| 1 |"));
+        let background = feature.background.as_ref().unwrap();
+        assert_eq!(text(background.header_span), "Background:");
+        assert_eq!(text(background.span), "Background:\n    Given a step");
+        let rule = &feature.rules[0];
+        assert_eq!(text(rule.header_span), "Rule: Bar");
+        assert_eq!(rule.span.end, feature.span.end);
+        let scenario = &rule.scenarios[0];
+        assert_eq!(text(scenario.header_span), "Scenario Outline: Baz");
+        assert_eq!(scenario.span.end, feature.span.end);
+        let step = &scenario.steps[0];
+        assert_eq!(text(step.header_span), "Given a <x>");
+        assert_eq!(
+            text(step.span),
+            "Given a <x>  \n        \"\"\"\n        text\n        \"\"\"",
+        );
+        let step = &scenario.steps[1];
+        assert_eq!(text(step.header_span), "When a table");
+        assert_eq!(text(step.span), "When a table\n        | a |");
+        assert_eq!(text(step.table.as_ref().unwrap().span), "| a |");
+        let examples = &scenario.examples[0];
+        assert_eq!(text(examples.header_span), "Examples: Qux");
+        assert_eq!(
+            text(examples.span),
+            "Examples: Qux\n        | x |\n        | 1 |",
+        );
+        assert_eq!(
+            text(examples.table.as_ref().unwrap().span),
+            "| x |\n        | 1 |",
+        );
+    }
+
+    #[test]
+    fn ids() {
+        let input = "Feature: Foo\n  Background:\n    Given a step\n\n  \
+                     Scenario Outline: Bar\n    Given a <step>\n      | a |\n\n    \
+                     @tag\n    Examples:\n      | step |\n      | b    |\n";
+        let feature = Feature::parse(input, GherkinEnv::default()).unwrap();
+
+        let background = feature.background.as_ref().unwrap();
+        assert_eq!(background.steps[0].id, "0");
+        assert_eq!(background.id, "1");
+        let scenario = &feature.scenarios[0];
+        assert_eq!(scenario.steps[0].table.as_ref().unwrap().rows[0].id, "2");
+        assert_eq!(scenario.steps[0].id, "3");
+        let examples = &scenario.examples[0];
+        let rows = &examples.table.as_ref().unwrap().rows;
+        assert_eq!([rows[0].id.as_str(), &rows[1].id], ["4", "5"]);
+        assert_eq!(examples.tags[0].id, "6");
+        assert_eq!(examples.id, "7");
+        assert_eq!(scenario.id, "8");
+        assert_eq!(feature.id, "9");
+
+        assert_eq!(
+            Feature::parse(input, GherkinEnv::default()).unwrap(),
+            feature
+        );
+        let env = || GherkinEnv::default().with_id_generator(UuidIdGenerator::default());
+        let feature = Feature::parse(input, env()).unwrap();
+        assert_eq!(feature.id.len(), 36);
+        assert_ne!(feature.scenarios[0].id, feature.id);
+        assert_eq!(Feature::parse(input, env()).unwrap(), feature);
+    }
+
+    #[test]
+    fn fragments() {
+        let step =
+            crate::Step::parse("  But a thing\n    | a | b |\n", GherkinEnv::default()).unwrap();
+        assert_eq!(step.ty, StepType::Given);
+        assert_eq!(step.value, "a thing");
+        assert_eq!(step.position, LineCol { line: 1, col: 3 });
+        assert_eq!(step.table.unwrap().values(), [["a", "b"]]);
+
+        let scenario = crate::Scenario::parse(
+            "# language: de\n@tag\nSzenariogrundriss: Foo\n  Angenommen <x>\n\n  Beispiele:\n    | x |\n    | 1 |",
+            GherkinEnv::default(),
+        )
+        .unwrap();
+        assert_eq!(scenario.keyword, "Szenariogrundriss");
+        assert_eq!(scenario.tags, ["tag"]);
+        assert_eq!(scenario.steps[0].keyword, "Angenommen ");
+        assert_eq!(scenario.examples[0].position.line, 6);
+
+        let background = crate::Background::parse(
+            "Contexte:\n  Soit a thing\n  Et another\n",
+            GherkinEnv::new("fr").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(background.steps.len(), 2);
+
+        let examples =
+            crate::Examples::parse("Examples: Foo\n  | a |\n  | 1 |\n", GherkinEnv::default())
+                .unwrap();
+        assert_eq!(examples.name.as_deref(), Some("Foo"));
+        assert_eq!(examples.table.unwrap().rows.len(), 2);
+
+        let table = crate::Table::parse("| a | b |\n| 1 | 2 |", GherkinEnv::default()).unwrap();
+        assert_eq!(table.values(), [["a", "b"], ["1", "2"]]);
+    }
+
+    #[test]
+    fn fragment_errors() {
+        let error =
+            crate::Step::parse("Given a step\nWhen another\n", GherkinEnv::default()).unwrap_err();
+        assert_eq!(error.position(), LineCol { line: 2, col: 1 });
+
+        let error = crate::Scenario::parse(
+            "Scenario: Foo\n  Given a\nScenario: Bar\n",
+            GherkinEnv::default(),
+        )
+        .unwrap_err();
+        assert_eq!(error.position().line, 3);
+
+        let error = crate::Table::parse("| a | b |\n| 1 |\n", GherkinEnv::default()).unwrap_err();
+        assert!(matches!(
+            error.error(),
+            Some(EnvError::InconsistentCellCount(_)),
+        ));
+
+        let error = crate::Step::parse("# language: xx\nGiven a step\n", GherkinEnv::default())
+            .unwrap_err();
+        assert!(matches!(
+            error.error(),
+            Some(EnvError::UnsupportedLanguage(l)) if l == "xx",
+        ));
+    }
+
     #[test]
     fn feature_only() {
         let env = GherkinEnv::default();
@@ -853,7 +1859,7 @@ Feature: Foo
                 .table
                 .as_ref()
                 .unwrap()
-                .rows,
+                .values(),
             vec![
                 vec!["value".to_owned()],
                 vec!["alu".to_owned()],
@@ -869,56 +1875,89 @@ Feature: Foo
     }
 
     #[test]
-    fn reject_invalid_example_escape_sequence() {
+    fn keep_unknown_example_escape_sequence() {
         let env = GherkinEnv::default();
         let input = r#"
 Feature: Foo
   Scenario: Bar
     Examples:
-      | value |
-      | \     |
+      | value   |
+      | \       |
+      | \o\no\  |
 "#;
-        assert!(gherkin_parser::feature(input, &env).is_err());
+        let feature = gherkin_parser::feature(input, &env).unwrap();
+
+        assert_eq!(
+            feature.scenarios[0].examples[0]
+                .table
+                .as_ref()
+                .unwrap()
+                .values(),
+            vec![
+                vec!["value".to_owned()],
+                vec!["\\".to_owned()],
+                vec!["\\o\no\\".to_owned()],
+            ]
+        );
     }
 
+    /// Fields of the upstream `gherkinDocument` not compared, as `(parent,
+    /// field)` pairs, with `*` matching any parent. The `uri` is relative to
+    /// the upstream repository.
+    const UNSUPPORTED_FIELDS: &[(&str, &str)] = &[("gherkinDocument", "uri"), ("pickle", "uri")];
+
     #[test]
     fn fixture_good() {
-        // We cannot handle missing features very well yet.
-        let skip = ["empty.feature", "incomplete_feature_3.feature"];
-        let mut failed = 0;
+        check_good_fixtures(|path, filename| {
+            parse_fixture(path, ParseMode::Lenient)
+                .map_err(|e| format!("failed to parse leniently: {}", e))?;
 
-        let d = env!("CARGO_MANIFEST_DIR");
-        let files = fs::read_dir(format!("{}/tests/fixtures/data/good/", d)).unwrap();
-        for file in files {
-            let file = file.unwrap();
-            let filename = file.file_name();
-            let filename = filename.to_str().unwrap();
-            if filename.ends_with(".feature") {
-                if skip.contains(&filename) {
-                    continue;
-                }
-                let res = std::panic::catch_unwind(|| {
-                    let env = GherkinEnv::default();
-                    let input =
-                        fs::read_to_string(format!("{}/tests/fixtures/data/good/{}", d, filename,))
-                            .unwrap();
-                    let feature = gherkin_parser::feature(&input, &env).unwrap();
-                    let fixture = fs::read_to_string(format!(
-                        "{}/tests/fixtures/data/good/{}.ast.ndjson",
-                        d, filename,
-                    ))
+            let fixture =
+                fs::read_to_string(path.with_file_name(format!("{}.ast.ndjson", filename)))
                     .unwrap();
+            let mut expected: serde_json::Value = serde_json::from_str(&fixture).unwrap();
+            strip_unsupported("", &mut expected);
+
+            // Strict mode is the one conforming to upstream.
+            let document = parse_fixture(path, ParseMode::Strict)
+                .map_err(|e| format!("failed to parse: {}", e))?;
+            diff("", &expected, &document_json(&document)).map_or(Ok(()), Err)
+        });
+    }
 
-                    check_ast(&feature, &fixture);
-                });
-                if res.is_err() {
-                    failed += 1;
-                    println!("{}", filename);
+    #[test]
+    fn fixture_good_pickles() {
+        check_good_fixtures(|path, filename| {
+            // The fixtures not coming from upstream have no pickles.
+            let Ok(fixture) =
+                fs::read_to_string(path.with_file_name(format!("{}.pickles.ndjson", filename)))
+            else {
+                return Ok(());
+            };
+            let mut expected = fixture
+                .lines()
+                .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+                .collect::<serde_json::Value>();
+            strip_unsupported("", &mut expected);
+            // Upstream gives the feature no id, so the ids of the pickles,
+            // following the ones of the document, are one more than upstream's.
+            for pickle in expected.as_array_mut().unwrap() {
+                let pickle = &mut pickle["pickle"];
+                next_id(&mut pickle["id"]);
+                for step in pickle["steps"].as_array_mut().unwrap() {
+                    next_id(&mut step["id"]);
                 }
             }
-        }
 
-        assert_eq!(failed, 0, "{} fixtures have failed", failed);
+            let document = parse_fixture(path, ParseMode::Strict)
+                .map_err(|e| format!("failed to parse: {}", e))?;
+            let pickles = document
+                .pickles(&mut IncrementingIdGenerator::default())
+                .iter()
+                .map(pickle_json)
+                .collect();
+            diff("", &expected, &pickles).map_or(Ok(()), Err)
+        });
     }
 
     #[test]
@@ -930,149 +1969,327 @@ Feature: Foo
             let filename = file.file_name();
             let filename = filename.to_str().unwrap();
             if filename.ends_with(".feature") {
+                let input =
+                    fs::read_to_string(format!("{}/tests/fixtures/data/bad/{}", d, filename))
+                        .unwrap();
                 let res = std::panic::catch_unwind(|| {
                     let env = GherkinEnv::default();
-                    let input =
-                        fs::read_to_string(format!("{}/tests/fixtures/data/bad/{}", d, filename,))
-                            .unwrap();
                     gherkin_parser::feature(&input, &env).unwrap()
                 });
+                assert!(res.is_err(), "{}: {:?}", filename, res.unwrap());
 
+                let env = GherkinEnv::default().with_mode(ParseMode::Strict);
+                let res = Feature::parse(&input, env);
                 assert!(res.is_err(), "{}: {:?}", filename, res.unwrap());
             }
         }
     }
 
-    // TODO: Actually generate `.ndjson` file from the AST to fully assert
-    //       fixtures.
-    fn check_ast(parsed: &Feature, ast_parsed: &str) {
-        let d: HashMap<String, serde_json::Value> = serde_json::from_str(ast_parsed).unwrap();
-
-        let document = d
-            .get("gherkinDocument")
-            .expect("There is no document in the file");
-        let feature = document
-            .get("feature")
-            .expect("There is no feature in the document");
-        let children = feature.get("children");
+    /// Increments the numeric `id`.
+    fn next_id(id: &mut serde_json::Value) {
+        let next = id.as_str().unwrap().parse::<usize>().unwrap() + 1;
+        *id = next.to_string().into();
+    }
 
-        if children.is_none() {
-            assert!(parsed.background.is_none());
-            assert_eq!(parsed.scenarios.len(), 0);
-            assert_eq!(parsed.rules.len(), 0);
-            return;
+    /// Runs the `check` on every good fixture, printing its outcome, and
+    /// fails with all the failed ones at the end.
+    fn check_good_fixtures(check: impl Fn(&std::path::Path, &str) -> Result<(), String>) {
+        let mut failures = vec![];
+        for path in good_fixtures() {
+            let filename = path.file_name().unwrap().to_str().unwrap();
+            match check(&path, filename) {
+                Ok(()) => println!("{} ... ok", filename),
+                Err(e) => {
+                    println!("{} ... FAILED", filename);
+                    failures.push(format!("{}: {}", filename, e));
+                }
+            }
         }
+        assert!(
+            failures.is_empty(),
+            "{} fixtures failed:\n{}",
+            failures.len(),
+            failures.join("\n"),
+        );
+    }
 
-        let mut backgrounds = 0;
-        let mut scenarios = 0;
-        let mut rules = 0;
-
-        let children = children.unwrap().as_array().unwrap();
-        for child in children {
-            if let Some(background) = child.get("background") {
-                let parsed_background = parsed.background.as_ref().unwrap();
-                let name = background.get("name").unwrap().as_str().unwrap();
-
-                assert_eq!(&parsed_background.name, name);
+    /// Returns the paths of the good fixtures, sorted.
+    fn good_fixtures() -> Vec<std::path::PathBuf> {
+        let d = env!("CARGO_MANIFEST_DIR");
+        let mut files = fs::read_dir(format!("{}/tests/fixtures/data/good/", d))
+            .unwrap()
+            .map(|f| f.unwrap().path())
+            .filter(|p| {
+                let p = p.to_string_lossy();
+                p.ends_with(".feature") || p.ends_with(".feature.md")
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+        files
+    }
 
-                let steps = background.get("steps");
+    fn parse_fixture(
+        path: &std::path::Path,
+        mode: ParseMode,
+    ) -> Result<GherkinDocument, ParseError> {
+        let input = fs::read_to_string(path).unwrap();
+        let env = GherkinEnv::default().with_mode(mode);
+        if path.to_string_lossy().ends_with(".md") {
+            GherkinDocument::parse_markdown(&input, env)
+        } else {
+            GherkinDocument::parse(&input, env)
+        }
+    }
 
-                if steps.is_none() {
-                    assert_eq!(parsed_background.steps.len(), 0);
-                    continue;
+    fn strip_unsupported(parent: &str, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(fields) => {
+                fields.retain(|field, _| {
+                    !UNSUPPORTED_FIELDS
+                        .iter()
+                        .any(|(p, f)| (*p == "*" || *p == parent) && f == field)
+                });
+                for (field, value) in fields {
+                    strip_unsupported(field, value);
+                }
+            }
+            serde_json::Value::Array(values) => {
+                for value in values {
+                    strip_unsupported(parent, value);
                 }
+            }
+            _ => {}
+        }
+    }
 
-                let steps = steps
-                    .expect("steps")
-                    .as_array()
-                    .expect("Steps must be an array");
-                assert!(
-                    check_steps(&parsed_background.steps, steps),
-                    "Background steps are different from fixture",
-                );
-
-                backgrounds += 1;
-            } else if let Some(json_scenario) = child.get("scenario") {
-                assert!(
-                    check_scenario(&parsed.scenarios, json_scenario),
-                    "Scenario steps are different from fixture",
-                );
-
-                scenarios += 1;
-            } else if let Some(json_rule) = child.get("rule") {
-                let json_rule_scenarios = json_rule
-                    .get("children")
-                    .and_then(serde_json::Value::as_array)
-                    .map(|children| {
-                        children
-                            .iter()
-                            .filter_map(|child| child.get("scenario"))
-                            .collect::<Vec<_>>()
-                    });
-                let json_rule_name = json_rule.get("name").unwrap().as_str().unwrap();
-
-                let exists = parsed.rules.iter().any(|rule| {
-                    if rule.name == json_rule_name {
-                        return if let Some(json_scenarios) = &json_rule_scenarios {
-                            json_scenarios.len() == rule.scenarios.len()
-                                && json_scenarios.iter().all(|json_scenario| {
-                                    check_scenario(&rule.scenarios, json_scenario)
-                                })
-                        } else {
-                            rule.scenarios.is_empty()
-                        };
+    /// Returns the first difference between the `expected` and `actual` JSON,
+    /// if any.
+    fn diff(
+        path: &str,
+        expected: &serde_json::Value,
+        actual: &serde_json::Value,
+    ) -> Option<String> {
+        use serde_json::Value;
+
+        match (expected, actual) {
+            (Value::Object(e), Value::Object(a)) => e
+                .keys()
+                .chain(a.keys().filter(|k| !e.contains_key(*k)))
+                .find_map(|k| {
+                    let path = format!("{}.{}", path, k);
+                    match (e.get(k), a.get(k)) {
+                        (Some(e), Some(a)) => diff(&path, e, a),
+                        (Some(_), None) => Some(format!("{} is missing", path)),
+                        (None, _) => Some(format!("{} is unexpected", path)),
                     }
-                    false
-                });
+                }),
+            (Value::Array(e), Value::Array(a)) if e.len() == a.len() => e
+                .iter()
+                .zip(a)
+                .enumerate()
+                .find_map(|(i, (e, a))| diff(&format!("{}[{}]", path, i), e, a)),
+            _ if expected == actual => None,
+            _ => Some(format!("{}: expected {}, got {}", path, expected, actual)),
+        }
+    }
 
-                assert!(exists, "Scenario rules are different from fixture");
+    fn document_json(document: &GherkinDocument) -> serde_json::Value {
+        use serde_json::json;
 
-                rules += 1;
-            } else {
-                panic!("Unknown child type: {:#?}", child);
-            }
+        let comments = document
+            .comments
+            .iter()
+            .map(|c| json!({ "location": location_json(c.position), "text": c.text }))
+            .collect::<Vec<_>>();
+        let mut json = json!({ "gherkinDocument": { "comments": comments } });
+        if let Some(feature) = &document.feature {
+            json["gherkinDocument"]["feature"] = feature_json(feature);
         }
+        json
+    }
 
-        if parsed.background.is_some() {
-            assert_eq!(1, backgrounds);
-        } else {
-            assert_eq!(0, backgrounds);
+    fn feature_json(feature: &Feature) -> serde_json::Value {
+        use serde_json::json;
+
+        let children = feature
+            .children()
+            .map(|child| match child {
+                FeatureChild::Background(b) => json!({ "background": background_json(b) }),
+                FeatureChild::Scenario(s) => json!({ "scenario": scenario_json(s) }),
+                FeatureChild::Rule(r) => {
+                    let children = r
+                        .children()
+                        .map(|child| match child {
+                            RuleChild::Background(b) => json!({ "background": background_json(b) }),
+                            RuleChild::Scenario(s) => json!({ "scenario": scenario_json(s) }),
+                        })
+                        .collect::<Vec<_>>();
+                    json!({
+                        "rule": {
+                            "location": location_json(r.position),
+                            "tags": tags_json(&r.tags),
+                            "keyword": r.keyword,
+                            "name": r.name,
+                            "description": r.description.as_ref().map_or("", |d| &d.text),
+                            "children": children,
+                            "id": r.id,
+                        },
+                    })
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut json = json!({
+            "location": location_json(feature.position),
+            "tags": tags_json(&feature.tags),
+            "keyword": feature.keyword,
+            "name": feature.name,
+            "language": feature.language,
+            "description": feature.description.as_ref().map_or("", |d| &d.text),
+            "children": children,
+        });
+        if feature.keyword.is_empty() {
+            // Upstream omits the keyword of Markdown without `# Feature:`.
+            json.as_object_mut().unwrap().remove("keyword");
         }
+        json
+    }
+
+    fn pickle_json(pickle: &Pickle) -> serde_json::Value {
+        use serde_json::json;
+
+        let steps = pickle
+            .steps
+            .iter()
+            .map(|s| {
+                let mut json = json!({ "astNodeIds": s.ast_node_ids, "id": s.id, "text": s.text });
+                if let Some(docstring) = &s.docstring {
+                    let mut d = json!({ "content": docstring.content });
+                    if let Some(media_type) = &docstring.media_type {
+                        d["mediaType"] = json!(media_type);
+                    }
+                    json["argument"] = json!({ "docString": d });
+                }
+                if let Some(table) = &s.table {
+                    let rows = table
+                        .iter()
+                        .map(|r| json!({ "cells": r.iter().map(|c| json!({ "value": c })).collect::<Vec<_>>() }))
+                        .collect::<Vec<_>>();
+                    json["argument"] = json!({ "dataTable": { "rows": rows } });
+                }
+                json
+            })
+            .collect::<Vec<_>>();
+        let tags = pickle
+            .tags
+            .iter()
+            .map(|t| json!({ "astNodeId": t.ast_node_id, "name": format!("@{}", t.name) }))
+            .collect::<Vec<_>>();
+        json!({
+            "pickle": {
+                "astNodeIds": pickle.ast_node_ids,
+                "id": pickle.id,
+                "language": pickle.language,
+                "name": pickle.name,
+                "steps": steps,
+                "tags": tags,
+            },
+        })
+    }
 
-        assert_eq!(parsed.scenarios.len(), scenarios);
-        assert_eq!(parsed.rules.len(), rules);
+    fn location_json(position: LineCol) -> serde_json::Value {
+        serde_json::json!({ "line": position.line, "column": position.col })
     }
 
-    fn check_scenario(parsed: &[Scenario], json: &serde_json::Value) -> bool {
-        let json_steps = json.get("steps").and_then(serde_json::Value::as_array);
-        let json_scenario_name = json.get("name").unwrap().as_str().unwrap();
+    fn tags_json(tags: &[Tag]) -> serde_json::Value {
+        tags.iter()
+            .map(|t| serde_json::json!({ "location": location_json(t.position), "name": t.to_string(), "id": t.id }))
+            .collect()
+    }
 
-        parsed.iter().any(|parsed| {
-            if parsed.name == json_scenario_name {
-                return if let Some(json_steps) = json_steps {
-                    check_steps(&parsed.steps, json_steps)
-                } else {
-                    parsed.steps.is_empty()
-                };
-            }
-            false
+    fn background_json(background: &Background) -> serde_json::Value {
+        serde_json::json!({
+            "location": location_json(background.position),
+            "keyword": background.keyword,
+            "name": background.name,
+            "description": background.description.as_ref().map_or("", |d| &d.text),
+            "steps": background.steps.iter().map(step_json).collect::<Vec<_>>(),
+            "id": background.id,
         })
     }
 
-    fn check_steps(parsed: &[Step], json: &[serde_json::Value]) -> bool {
-        if parsed.len() != json.len() {
-            return false;
-        }
+    fn scenario_json(scenario: &Scenario) -> serde_json::Value {
+        use serde_json::json;
 
-        for (step, json) in parsed.iter().zip(json) {
-            if step.keyword != json.get("keyword").unwrap().as_str().unwrap() {
-                return false;
-            }
-            if step.value != json.get("text").unwrap().as_str().unwrap() {
-                return false;
+        let examples = scenario
+            .examples
+            .iter()
+            .map(|e| {
+                let mut rows = e.table.iter().flat_map(|t| &t.rows).map(row_json);
+                let mut json = json!({
+                    "location": location_json(e.position),
+                    "tags": tags_json(&e.tags),
+                    "keyword": e.keyword,
+                    "name": e.name.as_deref().unwrap_or_default(),
+                    "description": e.description.as_ref().map_or("", |d| &d.text),
+                    "id": e.id,
+                });
+                if let Some(header) = rows.next() {
+                    json["tableHeader"] = header;
+                }
+                json["tableBody"] = rows.collect();
+                json
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "location": location_json(scenario.position),
+            "tags": tags_json(&scenario.tags),
+            "keyword": scenario.keyword,
+            "name": scenario.name,
+            "description": scenario.description.as_ref().map_or("", |d| &d.text),
+            "steps": scenario.steps.iter().map(step_json).collect::<Vec<_>>(),
+            "examples": examples,
+            "id": scenario.id,
+        })
+    }
+
+    fn step_json(step: &Step) -> serde_json::Value {
+        use serde_json::json;
+
+        let mut json = json!({
+            "location": location_json(step.position),
+            "keyword": step.keyword,
+            "text": step.value,
+            "id": step.id,
+        });
+        if let Some(docstring) = &step.docstring {
+            json["docString"] = json!({
+                "location": location_json(docstring.position),
+                "content": docstring.content,
+                "delimiter": docstring.delimiter,
+            });
+            if let Some(media_type) = &docstring.media_type {
+                json["docString"]["mediaType"] = json!(media_type);
             }
         }
+        if let Some(table) = &step.table {
+            json["dataTable"] = json!({
+                "location": location_json(table.position),
+                "rows": table.rows.iter().map(row_json).collect::<Vec<_>>(),
+            });
+        }
+        json
+    }
+
+    fn row_json(row: &TableRow) -> serde_json::Value {
+        use serde_json::json;
 
-        true
+        let cells = row
+            .cells
+            .iter()
+            .map(|c| json!({ "location": location_json(c.position), "value": c.value }))
+            .collect::<Vec<_>>();
+        json!({ "location": location_json(row.position), "cells": cells, "id": row.id })
     }
 }
@@ -0,0 +1,411 @@
+// Copyright (c) 2026  Brendan Molloy <brendan@bbqsrc.net>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Pickles, the test cases compiled from a [`GherkinDocument`] the way
+//! upstream compiles them.
+//!
+//! Every scenario is compiled into a [`Pickle`] with the steps of the
+//! backgrounds prepended, and every body row of the examples of a scenario
+//! outline into one with the `<placeholder>`s replaced by the values of the
+//! row.
+//!
+//! ```
+//! # use gherkin::{GherkinDocument, GherkinEnv, IncrementingIdGenerator};
+//! #
+//! let input = "Feature: Foo\n  \
+//!              Background:\n    Given a background step\n  \
+//!              @tag\n  Scenario Outline: Eating <count>\n    When I eat <count>\n    \
+//!              Examples:\n      | count |\n      | 1     |\n      | 2     |\n";
+//! let document = GherkinDocument::parse(input, GherkinEnv::default()).unwrap();
+//!
+//! let pickles = document.pickles(&mut IncrementingIdGenerator::default());
+//! assert_eq!(pickles.len(), 2);
+//! assert_eq!(pickles[1].name, "Eating 2");
+//! assert_eq!(pickles[1].steps[0].text, "a background step");
+//! assert_eq!(pickles[1].steps[1].text, "I eat 2");
+//! assert_eq!(pickles[1].tags[0].name, "tag");
+//!
+//! // The ids of the pickles follow the ones of the document, the last of
+//! // which is the feature's.
+//! assert_eq!(document.feature.as_ref().unwrap().id, "9");
+//! assert_eq!(pickles[0].steps[0].id, "10");
+//! ```
+
+use std::{iter, path::PathBuf};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    visit::{self, Context, Visit},
+    Background, Examples, Feature, GherkinDocument, IdGenerator, Rule, Scenario, Step, StepType,
+    TableRow, Tag,
+};
+
+/// A test case compiled from a scenario, or from a body row of the examples of
+/// a scenario outline
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "juniper", derive(juniper::GraphQLObject))]
+#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+pub struct Pickle {
+    /// The id of the pickle.
+    pub id: String,
+    /// The path of the document the pickle was compiled from, if known.
+    #[cfg_attr(feature = "juniper", graphql(ignore))]
+    pub uri: Option<PathBuf>,
+    /// The name of the scenario, with the placeholders replaced.
+    pub name: String,
+    /// The code of the language the document was parsed in, like `en`.
+    pub language: String,
+    /// The steps of the backgrounds, if the scenario has steps, followed by
+    /// the ones of the scenario.
+    pub steps: Vec<PickleStep>,
+    /// The tags of the feature, rule, scenario and examples.
+    pub tags: Vec<PickleTag>,
+    /// The ids of the scenario and of the body row of the examples, if any,
+    /// the pickle was compiled from.
+    pub ast_node_ids: Vec<String>,
+}
+
+/// A step of a [`Pickle`]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "juniper", derive(juniper::GraphQLObject))]
+#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+pub struct PickleStep {
+    /// The id of the step.
+    pub id: String,
+    /// The text of the step, with the placeholders replaced.
+    pub text: String,
+    /// The type of the step.
+    pub ty: StepType,
+    /// The docstring of the step, with the placeholders replaced, if any.
+    pub docstring: Option<PickleDocString>,
+    /// The values of the data table of the step, with the placeholders
+    /// replaced, if any.
+    pub table: Option<Vec<Vec<String>>>,
+    /// The ids of the step and of the body row of the examples, if any, the
+    /// pickle step was compiled from.
+    pub ast_node_ids: Vec<String>,
+}
+
+/// A docstring of a [`PickleStep`]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "juniper", derive(juniper::GraphQLObject))]
+#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+pub struct PickleDocString {
+    /// The content of the docstring.
+    pub content: String,
+    /// The media type of the docstring, if any.
+    pub media_type: Option<String>,
+}
+
+/// A tag of a [`Pickle`]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "juniper", derive(juniper::GraphQLObject))]
+#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+pub struct PickleTag {
+    /// The name of the tag, without the leading `@`.
+    pub name: String,
+    /// The id of the tag the pickle tag was compiled from.
+    pub ast_node_id: String,
+}
+
+impl GherkinDocument {
+    /// Compiles the scenarios of the document into [`Pickle`]s, in the order
+    /// they appear in the .feature file, generating their ids with the
+    /// `id_generator`.
+    ///
+    /// The ids of the pickles follow the ones of the document, as upstream
+    /// numbers them, so the `id_generator` is expected to be like the one the
+    /// document was parsed with when the parsing started: a fresh
+    /// [`IncrementingIdGenerator`](crate::IncrementingIdGenerator), or a
+    /// [`UuidIdGenerator`](crate::UuidIdGenerator) started with the same
+    /// input. The ids of the document are skipped before compiling, so unlike
+    /// upstream, which gives the feature no id, the ids of the pickles are
+    /// one more than upstream's.
+    ///
+    /// As upstream, every step gets its id before the pickle it belongs to,
+    /// and the examples without a table yield no pickles.
+    pub fn pickles(&self, id_generator: &mut dyn IdGenerator) -> Vec<Pickle> {
+        let Some(feature) = &self.feature else {
+            return vec![];
+        };
+        let mut ids = Ids(0);
+        ids.visit_document(self);
+        for _ in 0..ids.0 {
+            _ = id_generator.next_id();
+        }
+        let mut compiler = Compiler {
+            document: self,
+            language: &feature.language,
+            ids: id_generator,
+            pickles: vec![],
+        };
+
+        let tags = feature.tags.iter().collect::<Vec<_>>();
+        let background = feature
+            .background
+            .iter()
+            .flat_map(|b| &b.steps)
+            .collect::<Vec<_>>();
+        for scenario in &feature.scenarios {
+            compiler.scenario(&tags, &background, scenario);
+        }
+        for rule in &feature.rules {
+            compiler.rule(&tags, &background, rule);
+        }
+        compiler.pickles
+    }
+}
+
+/// Counter of the nodes of a document having an id.
+struct Ids(usize);
+
+impl<'ast> Visit<'ast> for Ids {
+    fn visit_feature(&mut self, node: &'ast Feature, ctx: Context<'ast>) {
+        self.0 += 1;
+        visit::visit_feature(self, node, ctx);
+    }
+
+    fn visit_rule(&mut self, node: &'ast Rule, ctx: Context<'ast>) {
+        self.0 += 1;
+        visit::visit_rule(self, node, ctx);
+    }
+
+    fn visit_background(&mut self, node: &'ast Background, ctx: Context<'ast>) {
+        self.0 += 1;
+        visit::visit_background(self, node, ctx);
+    }
+
+    fn visit_scenario(&mut self, node: &'ast Scenario, ctx: Context<'ast>) {
+        self.0 += 1;
+        visit::visit_scenario(self, node, ctx);
+    }
+
+    fn visit_examples(&mut self, node: &'ast Examples, ctx: Context<'ast>) {
+        self.0 += 1;
+        visit::visit_examples(self, node, ctx);
+    }
+
+    fn visit_step(&mut self, node: &'ast Step, ctx: Context<'ast>) {
+        self.0 += 1;
+        visit::visit_step(self, node, ctx);
+    }
+
+    fn visit_table_row(&mut self, node: &'ast TableRow, ctx: Context<'ast>) {
+        self.0 += 1;
+        visit::visit_table_row(self, node, ctx);
+    }
+
+    fn visit_tag(&mut self, _: &'ast Tag, _: Context<'ast>) {
+        self.0 += 1;
+    }
+}
+
+struct Compiler<'a> {
+    document: &'a GherkinDocument,
+    language: &'a str,
+    ids: &'a mut dyn IdGenerator,
+    pickles: Vec<Pickle>,
+}
+
+impl<'a> Compiler<'a> {
+    fn rule(&mut self, tags: &[&'a Tag], background: &[&'a Step], rule: &'a Rule) {
+        let tags = tags.iter().copied().chain(&rule.tags).collect::<Vec<_>>();
+        let background = background
+            .iter()
+            .copied()
+            .chain(rule.background.iter().flat_map(|b| &b.steps))
+            .collect::<Vec<_>>();
+        for scenario in &rule.scenarios {
+            self.scenario(&tags, &background, scenario);
+        }
+    }
+
+    fn scenario(&mut self, tags: &[&'a Tag], background: &[&'a Step], scenario: &'a Scenario) {
+        let tags = tags.iter().copied().chain(&scenario.tags);
+        if scenario.examples.is_empty() {
+            let steps = self.steps(background, scenario, None);
+            self.pickle(scenario.name.clone(), steps, tags, scenario, None);
+            return;
+        }
+
+        for examples in &scenario.examples {
+            let Some(header) = examples.header() else {
+                continue;
+            };
+            for row in examples.body() {
+                let values = Values { header, row };
+                let steps = self.steps(background, scenario, Some(values));
+                let tags = tags.clone().chain(&examples.tags);
+                let name = values.replace(&scenario.name);
+                self.pickle(name, steps, tags, scenario, Some(row));
+            }
+        }
+    }
+
+    fn pickle(
+        &mut self,
+        name: String,
+        steps: Vec<PickleStep>,
+        tags: impl Iterator<Item = &'a Tag>,
+        scenario: &Scenario,
+        row: Option<&TableRow>,
+    ) {
+        let pickle = Pickle {
+            id: self.ids.next_id(),
+            uri: self.document.uri.clone(),
+            name,
+            language: self.language.into(),
+            steps,
+            tags: tags
+                .map(|t| PickleTag {
+                    name: t.name.clone(),
+                    ast_node_id: t.id.clone(),
+                })
+                .collect(),
+            ast_node_ids: iter::once(&scenario.id)
+                .chain(row.map(|r| &r.id))
+                .cloned()
+                .collect(),
+        };
+        self.pickles.push(pickle);
+    }
+
+    /// Compiles the steps of the `background`, if the `scenario` has steps,
+    /// and the ones of the `scenario`.
+    fn steps(
+        &mut self,
+        background: &[&Step],
+        scenario: &Scenario,
+        values: Option<Values<'_>>,
+    ) -> Vec<PickleStep> {
+        if scenario.steps.is_empty() {
+            return vec![];
+        }
+        let mut steps = background
+            .iter()
+            .map(|s| self.step(s, None))
+            .collect::<Vec<_>>();
+        steps.extend(scenario.steps.iter().map(|s| self.step(s, values)));
+        steps
+    }
+
+    fn step(&mut self, step: &Step, values: Option<Values<'_>>) -> PickleStep {
+        let replace = |text: &str| match values {
+            Some(values) => values.replace(text),
+            None => text.to_owned(),
+        };
+
+        PickleStep {
+            id: self.ids.next_id(),
+            text: replace(&step.value),
+            ty: step.ty,
+            docstring: step.docstring.as_ref().map(|d| PickleDocString {
+                content: replace(&d.content),
+                media_type: d.media_type.as_deref().map(replace),
+            }),
+            table: step.table.as_ref().map(|t| {
+                t.rows
+                    .iter()
+                    .map(|r| r.cells.iter().map(|c| replace(&c.value)).collect())
+                    .collect()
+            }),
+            ast_node_ids: iter::once(&step.id)
+                .chain(values.map(|v| &v.row.id))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// A body row of examples along with their header.
+#[derive(Clone, Copy)]
+struct Values<'a> {
+    header: &'a TableRow,
+    row: &'a TableRow,
+}
+
+impl Values<'_> {
+    /// Replaces the placeholders of the `text` with the values of the row.
+    ///
+    /// As upstream, the columns replace their placeholders one after another,
+    /// so a value may have the placeholder of a following column replaced.
+    fn replace(self, text: &str) -> String {
+        let mut text = text.to_owned();
+        for (name, value) in self.header.cells.iter().zip(&self.row.cells) {
+            text = text.replace(&format!("<{}>", name.value), &value.value);
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{GherkinEnv, IncrementingIdGenerator};
+
+    fn pickles(input: &str) -> Vec<Pickle> {
+        let document = GherkinDocument::parse(input, GherkinEnv::default()).unwrap();
+        document.pickles(&mut IncrementingIdGenerator::default())
+    }
+
+    #[test]
+    fn outlines() {
+        let pickles = pickles(
+            "@a\nFeature: Foo\n  \
+             Rule: Bar\n    \
+             Background:\n      Given a <x> background step\n\n    \
+             @b\n    Scenario Outline: <x> and <y>\n      \
+             Given a <x>\n        \"\"\"<y>\n        <x> <y>\n        \"\"\"\n      \
+             When a table\n        | <x> | <xy> |\n\n      \
+             @c\n      Examples:\n        | x   | y | xy |\n        | <y> | 2 | 3  |\n\n      \
+             Examples:\n",
+        );
+        assert_eq!(pickles.len(), 1);
+        let pickle = &pickles[0];
+
+        // Columns replace their placeholders one after another.
+        assert_eq!(pickle.name, "2 and 2");
+        let texts = pickle.steps.iter().map(|s| &*s.text).collect::<Vec<_>>();
+        assert_eq!(texts, ["a <x> background step", "a 2", "a table"]);
+        let docstring = pickle.steps[1].docstring.as_ref().unwrap();
+        assert_eq!(docstring.content, "2 2");
+        assert_eq!(docstring.media_type.as_deref(), Some("2"));
+        assert_eq!(
+            pickle.steps[2].table,
+            Some(vec![vec!["2".into(), "3".into()]])
+        );
+
+        let tags = pickle.tags.iter().map(|t| &*t.name).collect::<Vec<_>>();
+        assert_eq!(tags, ["a", "b", "c"]);
+        assert_eq!(pickle.ast_node_ids.len(), 2);
+        assert_eq!(pickle.steps[0].ast_node_ids.len(), 1);
+        assert_eq!(pickle.steps[1].ast_node_ids[1], pickle.ast_node_ids[1]);
+    }
+
+    #[test]
+    fn backgrounds_need_steps() {
+        let pickles = pickles(
+            "Feature: Foo\n  Background:\n    Given a step\n\n  \
+             Scenario: Empty\n\n  Scenario: Full\n    When another step\n",
+        );
+        assert!(pickles[0].steps.is_empty());
+        let texts = pickles[1]
+            .steps
+            .iter()
+            .map(|s| &*s.text)
+            .collect::<Vec<_>>();
+        assert_eq!(texts, ["a step", "another step"]);
+        // Ids follow the 6 ones of the document, and steps get theirs before
+        // their pickle.
+        assert_eq!(pickles[0].id, "6");
+        assert_eq!(pickles[1].steps[0].id, "7");
+        assert_eq!(pickles[1].id, "9");
+    }
+}
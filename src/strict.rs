@@ -0,0 +1,650 @@
+// Copyright (c) 2026  Brendan Molloy <brendan@bbqsrc.net>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Checks of [`ParseMode::Strict`](crate::ParseMode::Strict).
+//!
+//! The input is matched line by line against the grammar of the upstream
+//! Gherkin parser, recovering from errors the same way it does, before being
+//! handed to the PEG grammar for building the AST.
+
+use std::fmt;
+
+use crate::{keywords::Keywords, parser::EnvError, GherkinEnv, LineCol, ParseError};
+
+/// Upstream stops collecting errors once there are more than this many.
+const MAX_ERRORS: usize = 10;
+
+/// An error found by [`ParseMode::Strict`](crate::ParseMode::Strict), worded
+/// the same way as the upstream Gherkin parser words its `parseError`s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrictError {
+    /// The `(line, col)` position of the error. Column is `0` for an
+    /// unexpected end of file.
+    pub position: LineCol,
+    /// The upstream-compatible description of the error.
+    pub message: String,
+    /// The tokens (`#EOF`, `#StepLine`, ...) expected at the position, if the
+    /// error is caused by an unexpected token.
+    pub expected: Vec<&'static str>,
+}
+
+impl fmt::Display for StrictError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({}:{}): {}",
+            self.position.line, self.position.col, self.message,
+        )
+    }
+}
+
+/// Checks the `input` against the upstream Gherkin grammar, recording the
+/// found errors as a fatal [`EnvError::Strict`] of the `env`.
+pub(crate) fn check(input: &str, env: &GherkinEnv) -> Result<(), ParseError> {
+    let mut lines = input
+        .split('\n')
+        .map(|l| l.strip_suffix('\r').unwrap_or(l))
+        .collect::<Vec<_>>();
+    if lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+
+    let mut checker = Checker {
+        keywords: env.keywords().clone(),
+        lines,
+        docstring_separator: None,
+        table: vec![],
+        errors: vec![],
+    };
+    checker.run();
+
+    let Some(first) = checker.errors.first() else {
+        return Ok(());
+    };
+    let error = ParseError {
+        position: first.position,
+        expected: first.expected.clone(),
+        error: None,
+    };
+    env.set_fatal_error(EnvError::Strict(checker.errors));
+    Err(error)
+}
+
+/// Kind of a line, as recognized by the upstream token matcher.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Token {
+    Eof,
+    Language,
+    TagLine,
+    FeatureLine,
+    RuleLine,
+    BackgroundLine,
+    ScenarioLine,
+    ExamplesLine,
+    StepLine,
+    DocStringSeparator,
+    TableRow,
+    Comment,
+    Empty,
+    Other,
+}
+
+impl Token {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Eof => "#EOF",
+            Self::Language => "#Language",
+            Self::TagLine => "#TagLine",
+            Self::FeatureLine => "#FeatureLine",
+            Self::RuleLine => "#RuleLine",
+            Self::BackgroundLine => "#BackgroundLine",
+            Self::ScenarioLine => "#ScenarioLine",
+            Self::ExamplesLine => "#ExamplesLine",
+            Self::StepLine => "#StepLine",
+            Self::DocStringSeparator => "#DocStringSeparator",
+            Self::TableRow => "#TableRow",
+            Self::Comment => "#Comment",
+            Self::Empty => "#Empty",
+            Self::Other => "#Other",
+        }
+    }
+}
+
+/// Block of the grammar having a keyword line and an optional description.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Block {
+    /// `Feature` or `Rule` header.
+    Header,
+    Background,
+    Scenario,
+    Examples,
+}
+
+/// Position inside a [`Block`] before its steps or table.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Part {
+    /// Right after the keyword line.
+    Title,
+    /// Inside the description.
+    Description,
+    /// Inside the comments following the description.
+    Comments,
+}
+
+/// Argument of the last step.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum StepArg {
+    None,
+    DataTable,
+    DocString,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum State {
+    Start,
+    Language,
+    FeatureTags,
+    RuleTags,
+    ScenarioTags,
+    ExamplesTags,
+    Block(Block, Part),
+    Step { background: bool, arg: StepArg },
+    DocString { background: bool },
+    ExamplesTable,
+}
+
+impl State {
+    /// Tokens accepted in this state, in the order upstream tries them.
+    fn expected(self) -> Vec<Token> {
+        use Token::*;
+
+        let follow = |scenario: bool| {
+            if scenario {
+                vec![TagLine, ExamplesLine, ScenarioLine, RuleLine]
+            } else {
+                vec![TagLine, ScenarioLine, RuleLine]
+            }
+        };
+
+        match self {
+            Self::Start => vec![Eof, Language, TagLine, FeatureLine, Comment, Empty],
+            Self::Language | Self::FeatureTags => vec![TagLine, FeatureLine, Comment, Empty],
+            Self::RuleTags => vec![TagLine, RuleLine, Comment, Empty],
+            Self::ScenarioTags => vec![TagLine, ScenarioLine, Comment, Empty],
+            Self::ExamplesTags => vec![TagLine, ExamplesLine, Comment, Empty],
+            Self::Block(block, part) => {
+                let mut tokens = match part {
+                    Part::Title => vec![Eof, Empty, Comment],
+                    Part::Description | Part::Comments => vec![Eof, Comment],
+                };
+                tokens.push(match block {
+                    Block::Header => BackgroundLine,
+                    Block::Background | Block::Scenario => StepLine,
+                    Block::Examples => TableRow,
+                });
+                tokens.extend(follow(matches!(block, Block::Scenario | Block::Examples)));
+                tokens.push(match part {
+                    Part::Title | Part::Description => Other,
+                    Part::Comments => Empty,
+                });
+                tokens
+            }
+            Self::Step { background, arg } => {
+                let mut tokens = vec![Eof];
+                match arg {
+                    StepArg::None => tokens.extend([TableRow, DocStringSeparator]),
+                    StepArg::DataTable => tokens.push(TableRow),
+                    StepArg::DocString => {}
+                }
+                tokens.push(StepLine);
+                tokens.extend(follow(!background));
+                tokens.extend([Comment, Empty]);
+                tokens
+            }
+            Self::DocString { .. } => vec![DocStringSeparator, Other],
+            Self::ExamplesTable => {
+                let mut tokens = vec![Eof, TableRow];
+                tokens.extend(follow(true));
+                tokens.extend([Comment, Empty]);
+                tokens
+            }
+        }
+    }
+
+    /// Indicates whether `Examples` may follow this state.
+    fn in_scenario(self) -> bool {
+        matches!(
+            self,
+            Self::Block(Block::Scenario | Block::Examples, _)
+                | Self::Step {
+                    background: false,
+                    ..
+                }
+                | Self::ExamplesTable,
+        )
+    }
+
+    /// Indicates whether this state is inside a data table or an `Examples`
+    /// table.
+    fn in_table(self) -> bool {
+        matches!(
+            self,
+            Self::Step {
+                arg: StepArg::DataTable,
+                ..
+            } | Self::ExamplesTable,
+        )
+    }
+}
+
+/// A single line of the input.
+struct Line<'a> {
+    /// 1-based number of the line.
+    number: usize,
+    /// Number of whitespace characters before the line's content.
+    indent: usize,
+    /// The line without its leading whitespace.
+    trimmed: &'a str,
+}
+
+impl<'a> Line<'a> {
+    fn new(number: usize, text: &'a str) -> Self {
+        let trimmed = text.trim_start();
+        Self {
+            number,
+            indent: text[..text.len() - trimmed.len()].chars().count(),
+            trimmed,
+        }
+    }
+
+    fn position(&self) -> LineCol {
+        LineCol {
+            line: self.number,
+            col: self.indent + 1,
+        }
+    }
+
+    fn starts_with_title_keyword(&self, keywords: &[&str]) -> bool {
+        keywords.iter().any(|k| {
+            self.trimmed
+                .strip_prefix(k)
+                .is_some_and(|rest| rest.starts_with(':'))
+        })
+    }
+
+    /// Name of the dialect requested by this line, if it's a
+    /// `# language: ...` directive.
+    fn language(&self) -> Option<&'a str> {
+        let rest = self.trimmed.strip_prefix('#')?.trim_start();
+        let rest = rest.strip_prefix("language")?.trim_start();
+        let lang = rest.strip_prefix(':')?.trim();
+        (!lang.is_empty()
+            && lang
+                .chars()
+                .all(|c| c.is_ascii_alphabetic() || c == '-' || c == '_'))
+        .then_some(lang)
+    }
+
+    /// Column of the first tag containing whitespace, if any.
+    fn invalid_tag(&self) -> Option<usize> {
+        let uncommented = self
+            .trimmed
+            .char_indices()
+            .find(|&(n, c)| c.is_whitespace() && self.trimmed[n + c.len_utf8()..].starts_with('#'))
+            .map_or(self.trimmed, |(n, _)| &self.trimmed[..n]);
+
+        let mut col = self.indent + 1;
+        for item in uncommented.split('@') {
+            let tag = item.trim_end();
+            if tag.is_empty() {
+                continue;
+            }
+            if tag.contains(char::is_whitespace) {
+                return Some(col);
+            }
+            col += item.chars().count() + 1;
+        }
+        None
+    }
+
+    /// Number of cells in this line, if it's a table row.
+    fn cell_count(&self) -> usize {
+        let mut chars = self.trimmed.chars();
+        let mut count = 0;
+        let mut first = true;
+        while let Some(c) = chars.next() {
+            match c {
+                '|' if first => first = false,
+                '|' => count += 1,
+                '\\' => _ = chars.next(),
+                _ => {}
+            }
+        }
+        count
+    }
+}
+
+struct Checker<'a> {
+    keywords: Keywords<'static>,
+    lines: Vec<&'a str>,
+    /// Separator of the currently open docstring.
+    docstring_separator: Option<&'static str>,
+    /// Positions and cell counts of the rows of the current table.
+    table: Vec<(LineCol, usize)>,
+    errors: Vec<StrictError>,
+}
+
+impl Checker<'_> {
+    fn run(&mut self) {
+        let mut state = State::Start;
+        for n in 0..=self.lines.len() {
+            let line = self.lines.get(n).map(|l| Line::new(n + 1, l));
+            state = self.advance(state, line.as_ref());
+            if self.errors.len() > MAX_ERRORS {
+                return;
+            }
+        }
+    }
+
+    fn add_error(&mut self, position: LineCol, message: String) {
+        self.errors.push(StrictError {
+            position,
+            message,
+            expected: vec![],
+        });
+    }
+
+    /// Matches the `line` in the given `state`, returning the next state.
+    fn advance(&mut self, state: State, line: Option<&Line<'_>>) -> State {
+        let expected = state.expected();
+        let Some(token) = expected.iter().copied().find(|t| self.matches(*t, line)) else {
+            let expected = expected.into_iter().map(Token::name).collect::<Vec<_>>();
+            let list = expected.join(", ");
+            self.errors.push(match line {
+                Some(line) => StrictError {
+                    position: line.position(),
+                    message: format!("expected: {list}, got '{}'", line.trimmed.trim()),
+                    expected,
+                },
+                None => StrictError {
+                    position: LineCol {
+                        line: self.lines.len() + 1,
+                        col: 0,
+                    },
+                    message: format!("unexpected end of file, expected: {list}"),
+                    expected,
+                },
+            });
+            return state;
+        };
+
+        if state.in_table() && !matches!(token, Token::TableRow | Token::Comment | Token::Empty) {
+            self.end_table();
+        }
+
+        match (token, state) {
+            (Token::Eof | Token::Empty, _) => state,
+            (Token::Language, _) => State::Language,
+            (Token::TagLine, State::Start | State::Language | State::FeatureTags) => {
+                State::FeatureTags
+            }
+            (Token::TagLine, State::RuleTags | State::ScenarioTags | State::ExamplesTags) => state,
+            (Token::TagLine, _) => {
+                let line = line.map_or(0, |l| l.number);
+                match self.lookahead(line) {
+                    Some(Token::ExamplesLine) if state.in_scenario() => State::ExamplesTags,
+                    Some(Token::ScenarioLine) => State::ScenarioTags,
+                    _ => State::RuleTags,
+                }
+            }
+            (Token::FeatureLine | Token::RuleLine, _) => State::Block(Block::Header, Part::Title),
+            (Token::BackgroundLine, _) => State::Block(Block::Background, Part::Title),
+            (Token::ScenarioLine, _) => State::Block(Block::Scenario, Part::Title),
+            (Token::ExamplesLine, _) => State::Block(Block::Examples, Part::Title),
+            (Token::StepLine, _) => State::Step {
+                background: matches!(
+                    state,
+                    State::Block(Block::Background, _)
+                        | State::Step {
+                            background: true,
+                            ..
+                        },
+                ),
+                arg: StepArg::None,
+            },
+            (Token::TableRow, _) => {
+                if let Some(line) = line {
+                    self.table.push((line.position(), line.cell_count()));
+                }
+                match state {
+                    State::Step { background, .. } => State::Step {
+                        background,
+                        arg: StepArg::DataTable,
+                    },
+                    _ => State::ExamplesTable,
+                }
+            }
+            (Token::DocStringSeparator, State::Step { background, .. }) => {
+                State::DocString { background }
+            }
+            (Token::DocStringSeparator, State::DocString { background }) => State::Step {
+                background,
+                arg: StepArg::DocString,
+            },
+            (Token::Comment, State::Block(block, Part::Title | Part::Description)) => {
+                State::Block(block, Part::Comments)
+            }
+            (Token::Other, State::Block(block, _)) => State::Block(block, Part::Description),
+            _ => state,
+        }
+    }
+
+    /// Checks whether the `line` is the given `token`.
+    fn matches(&mut self, token: Token, line: Option<&Line<'_>>) -> bool {
+        let Some(line) = line else {
+            return token == Token::Eof;
+        };
+
+        match token {
+            Token::Eof => false,
+            Token::Empty => line.trimmed.is_empty(),
+            Token::Other => true,
+            Token::Comment => line.trimmed.starts_with('#'),
+            Token::TableRow => line.trimmed.starts_with('|'),
+            Token::Language => {
+                let Some(lang) = line.language() else {
+                    return false;
+                };
+                match Keywords::get(lang) {
+                    Some(keywords) => {
+                        self.keywords = keywords;
+                        true
+                    }
+                    None => {
+                        self.add_error(line.position(), format!("Language not supported: {lang}"));
+                        false
+                    }
+                }
+            }
+            Token::TagLine => {
+                if !line.trimmed.starts_with('@') {
+                    return false;
+                }
+                match line.invalid_tag() {
+                    Some(col) => {
+                        self.add_error(
+                            LineCol {
+                                line: line.number,
+                                col,
+                            },
+                            "A tag may not contain whitespace".into(),
+                        );
+                        false
+                    }
+                    None => true,
+                }
+            }
+            Token::FeatureLine => line.starts_with_title_keyword(self.keywords.feature),
+            Token::RuleLine => line.starts_with_title_keyword(self.keywords.rule),
+            Token::BackgroundLine => line.starts_with_title_keyword(self.keywords.background),
+            Token::ScenarioLine => {
+                line.starts_with_title_keyword(self.keywords.scenario)
+                    || line.starts_with_title_keyword(self.keywords.scenario_outline)
+            }
+            Token::ExamplesLine => line.starts_with_title_keyword(self.keywords.examples),
+            Token::StepLine => [
+                self.keywords.given,
+                self.keywords.when,
+                self.keywords.then,
+                self.keywords.and,
+                self.keywords.but,
+            ]
+            .iter()
+            .flat_map(|kws| kws.iter())
+            .any(|k| line.trimmed.starts_with(k)),
+            Token::DocStringSeparator => match self.docstring_separator {
+                Some(sep) => {
+                    let closed = line.trimmed.starts_with(sep);
+                    if closed {
+                        self.docstring_separator = None;
+                    }
+                    closed
+                }
+                None => {
+                    let sep = ["\"\"\"", "```"]
+                        .iter()
+                        .copied()
+                        .find(|sep| line.trimmed.starts_with(*sep));
+                    self.docstring_separator = sep;
+                    sep.is_some()
+                }
+            },
+        }
+    }
+
+    /// Finds the first token after the tags starting at the given line.
+    fn lookahead(&self, line: usize) -> Option<Token> {
+        let line = self
+            .lines
+            .iter()
+            .enumerate()
+            .skip(line)
+            .map(|(n, l)| Line::new(n + 1, l))
+            .find(|l| !l.trimmed.is_empty() && !l.trimmed.starts_with(['#', '@']))?;
+
+        if line.starts_with_title_keyword(self.keywords.examples) {
+            Some(Token::ExamplesLine)
+        } else if line.starts_with_title_keyword(self.keywords.scenario)
+            || line.starts_with_title_keyword(self.keywords.scenario_outline)
+        {
+            Some(Token::ScenarioLine)
+        } else {
+            None
+        }
+    }
+
+    /// Checks that all the rows of the just finished table have the same
+    /// number of cells.
+    fn end_table(&mut self) {
+        let table = std::mem::take(&mut self.table);
+        let Some(&(_, width)) = table.first() else {
+            return;
+        };
+        if let Some(&(position, _)) = table.iter().find(|(_, n)| *n != width) {
+            self.add_error(position, "inconsistent cell count within the table".into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+    use crate::ParseMode;
+
+    fn strict_errors(input: &str) -> Vec<String> {
+        let env = GherkinEnv::default().with_mode(ParseMode::Strict);
+        match check(input, &env) {
+            Ok(()) => vec![],
+            Err(_) => match env.fatal_error.borrow_mut().take() {
+                Some(EnvError::Strict(errors)) => errors.iter().map(ToString::to_string).collect(),
+                e => panic!("unexpected error: {:?}", e),
+            },
+        }
+    }
+
+    #[test]
+    fn description_after_comment() {
+        let input = "Feature: Foo\n  description\n  # comment\n  more description\n";
+        assert_eq!(
+            strict_errors(input),
+            [
+                "(4:3): expected: #EOF, #Comment, #BackgroundLine, #TagLine, \
+                 #ScenarioLine, #RuleLine, #Empty, got 'more description'",
+            ],
+        );
+    }
+
+    #[test]
+    fn docstring_and_table() {
+        let input = "Feature: Foo\n  Scenario: Bar\n    Given baz\n      \"\"\"\n      \
+                     qux\n      \"\"\"\n      | a |\n";
+        assert_eq!(
+            strict_errors(input),
+            [
+                "(7:7): expected: #EOF, #StepLine, #TagLine, #ExamplesLine, \
+                 #ScenarioLine, #RuleLine, #Comment, #Empty, got '| a |'",
+            ],
+        );
+    }
+
+    #[test]
+    fn long_table_row() {
+        let input = "Feature: Foo\n  Scenario: Bar\n    Given baz\n      | a |\n      \
+                     | b | c |\n";
+        assert_eq!(
+            strict_errors(input),
+            ["(5:7): inconsistent cell count within the table"],
+        );
+    }
+
+    #[test]
+    fn fixture_good() {
+        let d = env!("CARGO_MANIFEST_DIR");
+        let files = fs::read_dir(format!("{}/tests/fixtures/data/good/", d)).unwrap();
+        for file in files {
+            let path = file.unwrap().path();
+            if path.extension().is_some_and(|ext| ext == "feature") {
+                let input = fs::read_to_string(&path).unwrap();
+                assert_eq!(strict_errors(&input), Vec::<String>::new(), "{path:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn fixture_bad() {
+        let d = env!("CARGO_MANIFEST_DIR");
+        let files = fs::read_dir(format!("{}/tests/fixtures/data/bad/", d)).unwrap();
+        for file in files {
+            let path = file.unwrap().path();
+            if path.extension().is_some_and(|ext| ext == "feature") {
+                let input = fs::read_to_string(&path).unwrap();
+                let fixture = fs::read_to_string(path.with_extension("feature.errors.ndjson"))
+                    .unwrap()
+                    .lines()
+                    .map(|l| {
+                        let error: serde_json::Value = serde_json::from_str(l).unwrap();
+                        error["parseError"]["message"].as_str().unwrap().to_owned()
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(strict_errors(&input), fixture, "{path:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,390 @@
+// Copyright (c) 2026  Brendan Molloy <brendan@bbqsrc.net>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Traversal of the AST by shared reference, in the style of `syn::visit`.
+//!
+//! Every method of [`Visit`] walks the children of its node by calling the
+//! function of the same name in this module, so an implementation overrides
+//! the methods of the nodes it's interested in, calling the function of the
+//! module to keep walking the children.
+//!
+//! ```
+//! # use gherkin::{visit::{self, Context, Visit}, Feature, GherkinEnv, Step};
+//! #
+//! #[derive(Default)]
+//! struct Steps<'ast>(Vec<(Option<&'ast str>, &'ast str)>);
+//!
+//! impl<'ast> Visit<'ast> for Steps<'ast> {
+//!     fn visit_step(&mut self, node: &'ast Step, ctx: Context<'ast>) {
+//!         self.0.push((ctx.rule.map(|r| r.name.as_str()), &node.value));
+//!         visit::visit_step(self, node, ctx);
+//!     }
+//! }
+//!
+//! let feature = Feature::parse(
+//!     "Feature: Foo\n\
+//!      \x20 Scenario: Bar\n\
+//!      \x20   Given a step\n\
+//!      \x20 Rule: Baz\n\
+//!      \x20   Scenario: Qux\n\
+//!      \x20     Given another step\n",
+//!     GherkinEnv::default(),
+//! )
+//! .unwrap();
+//!
+//! let mut steps = Steps::default();
+//! steps.visit_feature(&feature, Context::default());
+//! assert_eq!(steps.0, [(None, "a step"), (Some("Baz"), "another step")]);
+//! ```
+
+use crate::{
+    Background, Comment, Description, DocString, Examples, Feature, GherkinDocument,
+    LanguageDirective, Rule, Scenario, Step, Table, TableCell, TableRow, Tag,
+};
+
+/// Nodes enclosing the visited one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Context<'ast> {
+    /// The [`Feature`] enclosing the node, if any.
+    pub feature: Option<&'ast Feature>,
+    /// The [`Rule`] enclosing the node, if any.
+    pub rule: Option<&'ast Rule>,
+}
+
+/// Visitor of the AST by shared reference.
+///
+/// Every method walks the children of its node by default, via the function
+/// of the same name in the [`visit`](self) module.
+pub trait Visit<'ast> {
+    fn visit_document(&mut self, node: &'ast GherkinDocument) {
+        visit_document(self, node);
+    }
+
+    fn visit_language_directive(&mut self, node: &'ast LanguageDirective) {
+        let _ = node;
+    }
+
+    fn visit_comment(&mut self, node: &'ast Comment) {
+        let _ = node;
+    }
+
+    fn visit_feature(&mut self, node: &'ast Feature, ctx: Context<'ast>) {
+        visit_feature(self, node, ctx);
+    }
+
+    fn visit_rule(&mut self, node: &'ast Rule, ctx: Context<'ast>) {
+        visit_rule(self, node, ctx);
+    }
+
+    fn visit_background(&mut self, node: &'ast Background, ctx: Context<'ast>) {
+        visit_background(self, node, ctx);
+    }
+
+    fn visit_scenario(&mut self, node: &'ast Scenario, ctx: Context<'ast>) {
+        visit_scenario(self, node, ctx);
+    }
+
+    fn visit_examples(&mut self, node: &'ast Examples, ctx: Context<'ast>) {
+        visit_examples(self, node, ctx);
+    }
+
+    fn visit_step(&mut self, node: &'ast Step, ctx: Context<'ast>) {
+        visit_step(self, node, ctx);
+    }
+
+    fn visit_docstring(&mut self, node: &'ast DocString, ctx: Context<'ast>) {
+        let _ = (node, ctx);
+    }
+
+    fn visit_table(&mut self, node: &'ast Table, ctx: Context<'ast>) {
+        visit_table(self, node, ctx);
+    }
+
+    fn visit_table_row(&mut self, node: &'ast TableRow, ctx: Context<'ast>) {
+        visit_table_row(self, node, ctx);
+    }
+
+    fn visit_table_cell(&mut self, node: &'ast TableCell, ctx: Context<'ast>) {
+        let _ = (node, ctx);
+    }
+
+    fn visit_tag(&mut self, node: &'ast Tag, ctx: Context<'ast>) {
+        let _ = (node, ctx);
+    }
+
+    fn visit_description(&mut self, node: &'ast Description, ctx: Context<'ast>) {
+        let _ = (node, ctx);
+    }
+}
+
+/// Visits the [`LanguageDirective`], the [`Comment`]s and the [`Feature`] of
+/// the `node`.
+pub fn visit_document<'ast, V>(v: &mut V, node: &'ast GherkinDocument)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    if let Some(directive) = &node.language_directive {
+        v.visit_language_directive(directive);
+    }
+    for comment in &node.comments {
+        v.visit_comment(comment);
+    }
+    if let Some(feature) = &node.feature {
+        v.visit_feature(feature, Context::default());
+    }
+}
+
+/// Visits the [`Tag`]s, the [`Description`] and the children of the `node`,
+/// in the order of [`Feature::children()`].
+pub fn visit_feature<'ast, V>(v: &mut V, node: &'ast Feature, ctx: Context<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for tag in &node.tags {
+        v.visit_tag(tag, ctx);
+    }
+    if let Some(description) = &node.description {
+        v.visit_description(description, ctx);
+    }
+
+    let ctx = Context {
+        feature: Some(node),
+        ..ctx
+    };
+    if let Some(background) = &node.background {
+        v.visit_background(background, ctx);
+    }
+    for scenario in &node.scenarios {
+        v.visit_scenario(scenario, ctx);
+    }
+    for rule in &node.rules {
+        v.visit_rule(rule, ctx);
+    }
+}
+
+/// Visits the [`Tag`]s, the [`Description`] and the children of the `node`,
+/// in the order of [`Rule::children()`].
+pub fn visit_rule<'ast, V>(v: &mut V, node: &'ast Rule, ctx: Context<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for tag in &node.tags {
+        v.visit_tag(tag, ctx);
+    }
+    if let Some(description) = &node.description {
+        v.visit_description(description, ctx);
+    }
+
+    let ctx = Context {
+        rule: Some(node),
+        ..ctx
+    };
+    if let Some(background) = &node.background {
+        v.visit_background(background, ctx);
+    }
+    for scenario in &node.scenarios {
+        v.visit_scenario(scenario, ctx);
+    }
+}
+
+/// Visits the [`Description`] and the [`Step`]s of the `node`.
+pub fn visit_background<'ast, V>(v: &mut V, node: &'ast Background, ctx: Context<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    if let Some(description) = &node.description {
+        v.visit_description(description, ctx);
+    }
+    for step in &node.steps {
+        v.visit_step(step, ctx);
+    }
+}
+
+/// Visits the [`Tag`]s, the [`Description`], the [`Step`]s and the
+/// [`Examples`] of the `node`.
+pub fn visit_scenario<'ast, V>(v: &mut V, node: &'ast Scenario, ctx: Context<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for tag in &node.tags {
+        v.visit_tag(tag, ctx);
+    }
+    if let Some(description) = &node.description {
+        v.visit_description(description, ctx);
+    }
+    for step in &node.steps {
+        v.visit_step(step, ctx);
+    }
+    for examples in &node.examples {
+        v.visit_examples(examples, ctx);
+    }
+}
+
+/// Visits the [`Tag`]s, the [`Description`] and the [`Table`] of the `node`.
+pub fn visit_examples<'ast, V>(v: &mut V, node: &'ast Examples, ctx: Context<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for tag in &node.tags {
+        v.visit_tag(tag, ctx);
+    }
+    if let Some(description) = &node.description {
+        v.visit_description(description, ctx);
+    }
+    if let Some(table) = &node.table {
+        v.visit_table(table, ctx);
+    }
+}
+
+/// Visits the [`DocString`] and the [`Table`] of the `node`.
+pub fn visit_step<'ast, V>(v: &mut V, node: &'ast Step, ctx: Context<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    if let Some(docstring) = &node.docstring {
+        v.visit_docstring(docstring, ctx);
+    }
+    if let Some(table) = &node.table {
+        v.visit_table(table, ctx);
+    }
+}
+
+/// Visits the [`TableRow`]s of the `node`.
+pub fn visit_table<'ast, V>(v: &mut V, node: &'ast Table, ctx: Context<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for row in &node.rows {
+        v.visit_table_row(row, ctx);
+    }
+}
+
+/// Visits the [`TableCell`]s of the `node`.
+pub fn visit_table_row<'ast, V>(v: &mut V, node: &'ast TableRow, ctx: Context<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for cell in &node.cells {
+        v.visit_table_cell(cell, ctx);
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod test {
+    use super::*;
+    use crate::GherkinEnv;
+
+    /// Records the ids of the visited nodes, along with the ids of the
+    /// enclosing ones.
+    #[derive(Default)]
+    struct Ids(Vec<String>);
+
+    impl Ids {
+        fn push(&mut self, kind: &str, id: &str, ctx: Context<'_>) {
+            self.0.push(format!(
+                "{} {} in {}/{}",
+                kind,
+                id,
+                ctx.feature.map_or("-", |f| &f.id),
+                ctx.rule.map_or("-", |r| &r.id),
+            ));
+        }
+    }
+
+    impl<'ast> Visit<'ast> for Ids {
+        fn visit_comment(&mut self, node: &'ast Comment) {
+            self.0.push(format!("comment {}", node.text));
+        }
+
+        fn visit_feature(&mut self, node: &'ast Feature, ctx: Context<'ast>) {
+            self.push("feature", &node.id, ctx);
+            visit_feature(self, node, ctx);
+        }
+
+        fn visit_rule(&mut self, node: &'ast Rule, ctx: Context<'ast>) {
+            self.push("rule", &node.id, ctx);
+            visit_rule(self, node, ctx);
+        }
+
+        fn visit_background(&mut self, node: &'ast Background, ctx: Context<'ast>) {
+            self.push("background", &node.id, ctx);
+            visit_background(self, node, ctx);
+        }
+
+        fn visit_scenario(&mut self, node: &'ast Scenario, ctx: Context<'ast>) {
+            self.push("scenario", &node.id, ctx);
+            visit_scenario(self, node, ctx);
+        }
+
+        fn visit_examples(&mut self, node: &'ast Examples, ctx: Context<'ast>) {
+            self.push("examples", &node.id, ctx);
+            visit_examples(self, node, ctx);
+        }
+
+        fn visit_step(&mut self, node: &'ast Step, ctx: Context<'ast>) {
+            self.push("step", &node.id, ctx);
+            visit_step(self, node, ctx);
+        }
+
+        fn visit_docstring(&mut self, node: &'ast DocString, ctx: Context<'ast>) {
+            self.push("docstring", &node.content, ctx);
+        }
+
+        fn visit_table_row(&mut self, node: &'ast TableRow, ctx: Context<'ast>) {
+            self.push("row", &node.id, ctx);
+            visit_table_row(self, node, ctx);
+        }
+
+        fn visit_tag(&mut self, node: &'ast Tag, ctx: Context<'ast>) {
+            self.push("tag", &node.id, ctx);
+        }
+    }
+
+    #[test]
+    fn visits_in_document_order() {
+        let input = "# A comment\n\
+                     Feature: Foo\n  \
+                       Background:\n    \
+                         Given a step\n  \
+                       Scenario: Bar\n    \
+                         Given a step\n      \
+                           \"\"\"\n      \
+                           content\n      \
+                           \"\"\"\n  \
+                       @tag\n  \
+                       Rule: Baz\n    \
+                         Scenario Outline: Qux\n      \
+                           Given a step\n      \
+                           Examples:\n        \
+                             | a |\n        \
+                             | 1 |\n";
+        let document = GherkinDocument::parse(input, GherkinEnv::default()).unwrap();
+
+        let mut ids = Ids::default();
+        ids.visit_document(&document);
+        assert_eq!(
+            ids.0,
+            [
+                "comment # A comment",
+                "feature 11 in -/-",
+                "background 1 in 11/-",
+                "step 0 in 11/-",
+                "scenario 3 in 11/-",
+                "step 2 in 11/-",
+                "docstring content in 11/-",
+                "rule 10 in 11/-",
+                "tag 9 in 11/-",
+                "scenario 8 in 11/10",
+                "step 4 in 11/10",
+                "examples 7 in 11/10",
+                "row 5 in 11/10",
+                "row 6 in 11/10",
+            ],
+        );
+    }
+}
@@ -0,0 +1,368 @@
+// Copyright (c) 2026  Brendan Molloy <brendan@bbqsrc.net>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Traversal of the AST by mutable reference, in the style of
+//! `syn::visit_mut`.
+//!
+//! Works like the [`visit`](crate::visit) module, except that the enclosing
+//! [`Feature`] and [`Rule`] can't be borrowed while their children are
+//! mutated, so the [`Context`] only has the parts of them that aren't
+//! visited after them, as an [`Enclosing`] node.
+//!
+//! ```
+//! # use gherkin::{visit_mut::{self, Context, VisitMut}, Feature, GherkinEnv, Step};
+//! #
+//! struct Prefix;
+//!
+//! impl VisitMut for Prefix {
+//!     fn visit_step_mut(&mut self, node: &mut Step, ctx: Context<'_>) {
+//!         if let Some(rule) = ctx.rule {
+//!             node.value = format!("{}: {}", rule.name, node.value);
+//!         }
+//!         visit_mut::visit_step_mut(self, node, ctx);
+//!     }
+//! }
+//!
+//! let mut feature = Feature::parse(
+//!     "Feature: Foo\n\
+//!      \x20 Rule: Bar\n\
+//!      \x20   Scenario: Baz\n\
+//!      \x20     Given a step\n",
+//!     GherkinEnv::default(),
+//! )
+//! .unwrap();
+//!
+//! Prefix.visit_feature_mut(&mut feature, Context::default());
+//! assert_eq!(feature.rules[0].scenarios[0].steps[0].value, "Bar: a step");
+//! ```
+
+use crate::{
+    Background, Comment, Description, DocString, Examples, Feature, GherkinDocument,
+    LanguageDirective, LineCol, Rule, Scenario, Span, Step, Table, TableCell, TableRow, Tag,
+};
+
+/// Nodes enclosing the visited one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Context<'a> {
+    /// The [`Feature`] enclosing the node, if any.
+    pub feature: Option<Enclosing<'a>>,
+    /// The [`Rule`] enclosing the node, if any.
+    pub rule: Option<Enclosing<'a>>,
+}
+
+/// The parts of an enclosing [`Feature`] or [`Rule`] that aren't visited after
+/// it.
+#[derive(Debug, Clone, Copy)]
+pub struct Enclosing<'a> {
+    /// The keyword of the node.
+    pub keyword: &'a str,
+    /// The name of the node.
+    pub name: &'a str,
+    /// The tags of the node.
+    pub tags: &'a [Tag],
+    /// The id of the node.
+    pub id: &'a str,
+    /// The `(start, end)` offset the node spans in the source.
+    pub span: Span,
+    /// The `(line, col)` position the node starts at in the source.
+    pub position: LineCol,
+}
+
+/// Visitor of the AST by mutable reference.
+///
+/// Every method walks the children of its node by default, via the function
+/// of the same name in the [`visit_mut`](self) module.
+pub trait VisitMut {
+    fn visit_document_mut(&mut self, node: &mut GherkinDocument) {
+        visit_document_mut(self, node);
+    }
+
+    fn visit_language_directive_mut(&mut self, node: &mut LanguageDirective) {
+        let _ = node;
+    }
+
+    fn visit_comment_mut(&mut self, node: &mut Comment) {
+        let _ = node;
+    }
+
+    fn visit_feature_mut(&mut self, node: &mut Feature, ctx: Context<'_>) {
+        visit_feature_mut(self, node, ctx);
+    }
+
+    fn visit_rule_mut(&mut self, node: &mut Rule, ctx: Context<'_>) {
+        visit_rule_mut(self, node, ctx);
+    }
+
+    fn visit_background_mut(&mut self, node: &mut Background, ctx: Context<'_>) {
+        visit_background_mut(self, node, ctx);
+    }
+
+    fn visit_scenario_mut(&mut self, node: &mut Scenario, ctx: Context<'_>) {
+        visit_scenario_mut(self, node, ctx);
+    }
+
+    fn visit_examples_mut(&mut self, node: &mut Examples, ctx: Context<'_>) {
+        visit_examples_mut(self, node, ctx);
+    }
+
+    fn visit_step_mut(&mut self, node: &mut Step, ctx: Context<'_>) {
+        visit_step_mut(self, node, ctx);
+    }
+
+    fn visit_docstring_mut(&mut self, node: &mut DocString, ctx: Context<'_>) {
+        let _ = (node, ctx);
+    }
+
+    fn visit_table_mut(&mut self, node: &mut Table, ctx: Context<'_>) {
+        visit_table_mut(self, node, ctx);
+    }
+
+    fn visit_table_row_mut(&mut self, node: &mut TableRow, ctx: Context<'_>) {
+        visit_table_row_mut(self, node, ctx);
+    }
+
+    fn visit_table_cell_mut(&mut self, node: &mut TableCell, ctx: Context<'_>) {
+        let _ = (node, ctx);
+    }
+
+    fn visit_tag_mut(&mut self, node: &mut Tag, ctx: Context<'_>) {
+        let _ = (node, ctx);
+    }
+
+    fn visit_description_mut(&mut self, node: &mut Description, ctx: Context<'_>) {
+        let _ = (node, ctx);
+    }
+}
+
+/// Visits the [`LanguageDirective`], the [`Comment`]s and the [`Feature`] of
+/// the `node`.
+pub fn visit_document_mut<V>(v: &mut V, node: &mut GherkinDocument)
+where
+    V: VisitMut + ?Sized,
+{
+    if let Some(directive) = &mut node.language_directive {
+        v.visit_language_directive_mut(directive);
+    }
+    for comment in &mut node.comments {
+        v.visit_comment_mut(comment);
+    }
+    if let Some(feature) = &mut node.feature {
+        v.visit_feature_mut(feature, Context::default());
+    }
+}
+
+/// Visits the [`Tag`]s, the [`Description`] and the children of the `node`,
+/// in the order of [`Feature::children()`].
+pub fn visit_feature_mut<V>(v: &mut V, node: &mut Feature, ctx: Context<'_>)
+where
+    V: VisitMut + ?Sized,
+{
+    for tag in &mut node.tags {
+        v.visit_tag_mut(tag, ctx);
+    }
+    if let Some(description) = &mut node.description {
+        v.visit_description_mut(description, ctx);
+    }
+
+    let ctx = Context {
+        feature: Some(Enclosing {
+            keyword: &node.keyword,
+            name: &node.name,
+            tags: &node.tags,
+            id: &node.id,
+            span: node.span,
+            position: node.position,
+        }),
+        ..ctx
+    };
+    if let Some(background) = &mut node.background {
+        v.visit_background_mut(background, ctx);
+    }
+    for scenario in &mut node.scenarios {
+        v.visit_scenario_mut(scenario, ctx);
+    }
+    for rule in &mut node.rules {
+        v.visit_rule_mut(rule, ctx);
+    }
+}
+
+/// Visits the [`Tag`]s, the [`Description`] and the children of the `node`,
+/// in the order of [`Rule::children()`].
+pub fn visit_rule_mut<V>(v: &mut V, node: &mut Rule, ctx: Context<'_>)
+where
+    V: VisitMut + ?Sized,
+{
+    for tag in &mut node.tags {
+        v.visit_tag_mut(tag, ctx);
+    }
+    if let Some(description) = &mut node.description {
+        v.visit_description_mut(description, ctx);
+    }
+
+    let ctx = Context {
+        rule: Some(Enclosing {
+            keyword: &node.keyword,
+            name: &node.name,
+            tags: &node.tags,
+            id: &node.id,
+            span: node.span,
+            position: node.position,
+        }),
+        ..ctx
+    };
+    if let Some(background) = &mut node.background {
+        v.visit_background_mut(background, ctx);
+    }
+    for scenario in &mut node.scenarios {
+        v.visit_scenario_mut(scenario, ctx);
+    }
+}
+
+/// Visits the [`Description`] and the [`Step`]s of the `node`.
+pub fn visit_background_mut<V>(v: &mut V, node: &mut Background, ctx: Context<'_>)
+where
+    V: VisitMut + ?Sized,
+{
+    if let Some(description) = &mut node.description {
+        v.visit_description_mut(description, ctx);
+    }
+    for step in &mut node.steps {
+        v.visit_step_mut(step, ctx);
+    }
+}
+
+/// Visits the [`Tag`]s, the [`Description`], the [`Step`]s and the
+/// [`Examples`] of the `node`.
+pub fn visit_scenario_mut<V>(v: &mut V, node: &mut Scenario, ctx: Context<'_>)
+where
+    V: VisitMut + ?Sized,
+{
+    for tag in &mut node.tags {
+        v.visit_tag_mut(tag, ctx);
+    }
+    if let Some(description) = &mut node.description {
+        v.visit_description_mut(description, ctx);
+    }
+    for step in &mut node.steps {
+        v.visit_step_mut(step, ctx);
+    }
+    for examples in &mut node.examples {
+        v.visit_examples_mut(examples, ctx);
+    }
+}
+
+/// Visits the [`Tag`]s, the [`Description`] and the [`Table`] of the `node`.
+pub fn visit_examples_mut<V>(v: &mut V, node: &mut Examples, ctx: Context<'_>)
+where
+    V: VisitMut + ?Sized,
+{
+    for tag in &mut node.tags {
+        v.visit_tag_mut(tag, ctx);
+    }
+    if let Some(description) = &mut node.description {
+        v.visit_description_mut(description, ctx);
+    }
+    if let Some(table) = &mut node.table {
+        v.visit_table_mut(table, ctx);
+    }
+}
+
+/// Visits the [`DocString`] and the [`Table`] of the `node`.
+pub fn visit_step_mut<V>(v: &mut V, node: &mut Step, ctx: Context<'_>)
+where
+    V: VisitMut + ?Sized,
+{
+    if let Some(docstring) = &mut node.docstring {
+        v.visit_docstring_mut(docstring, ctx);
+    }
+    if let Some(table) = &mut node.table {
+        v.visit_table_mut(table, ctx);
+    }
+}
+
+/// Visits the [`TableRow`]s of the `node`.
+pub fn visit_table_mut<V>(v: &mut V, node: &mut Table, ctx: Context<'_>)
+where
+    V: VisitMut + ?Sized,
+{
+    for row in &mut node.rows {
+        v.visit_table_row_mut(row, ctx);
+    }
+}
+
+/// Visits the [`TableCell`]s of the `node`.
+pub fn visit_table_row_mut<V>(v: &mut V, node: &mut TableRow, ctx: Context<'_>)
+where
+    V: VisitMut + ?Sized,
+{
+    for cell in &mut node.cells {
+        v.visit_table_cell_mut(cell, ctx);
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod test {
+    use super::*;
+    use crate::GherkinEnv;
+
+    /// Tags every scenario with the tags of its enclosing feature and rule.
+    struct InheritTags;
+
+    impl VisitMut for InheritTags {
+        fn visit_scenario_mut(&mut self, node: &mut Scenario, ctx: Context<'_>) {
+            let inherited = ctx.feature.into_iter().chain(ctx.rule);
+            let tags = inherited.flat_map(|e| e.tags).cloned().collect::<Vec<_>>();
+            node.tags.splice(0..0, tags);
+            visit_scenario_mut(self, node, ctx);
+        }
+
+        fn visit_table_cell_mut(&mut self, node: &mut TableCell, _: Context<'_>) {
+            node.value = node.value.to_uppercase();
+        }
+    }
+
+    #[test]
+    fn mutates_with_context() {
+        let input = "@foo\n\
+                     Feature: Foo\n  \
+                       Scenario: Bar\n    \
+                         Given a step\n  \
+                       @baz\n  \
+                       Rule: Baz\n    \
+                         @qux\n    \
+                         Scenario Outline: Qux\n      \
+                           Given <a>\n      \
+                           Examples:\n        \
+                             | a |\n        \
+                             | b |\n";
+        let mut document = GherkinDocument::parse(input, GherkinEnv::default()).unwrap();
+
+        InheritTags.visit_document_mut(&mut document);
+
+        let feature = document.feature.unwrap();
+        assert_eq!(feature.tag_names().collect::<Vec<_>>(), ["foo"]);
+        assert_eq!(
+            feature.scenarios[0].tag_names().collect::<Vec<_>>(),
+            ["foo"]
+        );
+        let rule = &feature.rules[0];
+        assert_eq!(rule.tag_names().collect::<Vec<_>>(), ["baz"]);
+        assert_eq!(
+            rule.scenarios[0].tag_names().collect::<Vec<_>>(),
+            ["foo", "baz", "qux"]
+        );
+        assert_eq!(
+            rule.scenarios[0].examples[0]
+                .table
+                .as_ref()
+                .unwrap()
+                .values(),
+            [["A"], ["B"]],
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use std::path::Path;
 
-use gherkin::{Feature, ParseFileError};
+use gherkin::{EnvError, Feature, GherkinEnv, ParseFileError, ParseMode};
 
 fn load_feature<P: AsRef<Path>>(path: P) -> Result<Feature, ParseFileError> {
     Feature::parse_path(path.as_ref(), Default::default())
@@ -15,7 +15,7 @@ fn inconsistent_cell_count() {
         ParseFileError::Parsing { error, .. } => {
             let error = error.unwrap();
             match error {
-                gherkin::EnvError::InconsistentCellCount(_) => {}
+                EnvError::InconsistentCellCount(_) => {}
                 _ => panic!("Invalid error"),
             }
         }
@@ -51,3 +51,23 @@ fn unexpected_eof() {
 fn whitespace_in_tags() {
     let _ = load_feature("./tests/fixtures/data/bad/whitespace_in_tags.feature").unwrap_err();
 }
+
+#[test]
+fn strict_mode_errors() {
+    let error = Feature::parse_path(
+        "./tests/fixtures/data/bad/multiple_parser_errors.feature",
+        GherkinEnv::default().with_mode(ParseMode::Strict),
+    )
+    .unwrap_err();
+    match error {
+        ParseFileError::Parsing {
+            error: Some(EnvError::Strict(errors)),
+            ..
+        } => {
+            assert_eq!(errors.len(), 2);
+            assert_eq!(errors[0].position.line, 2);
+            assert_eq!(errors[1].position.line, 9);
+        }
+        _ => panic!("Invalid error"),
+    }
+}